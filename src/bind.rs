@@ -196,6 +196,71 @@ impl Bind {
 }
 
 impl Bind {
+	/// Replaces scheme name `old` with `new` in all the targets of the
+	/// bind (`"old"`, `"old/slot"`, `"old/slot/sector"`).
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::bind::Bind;
+	/// let mut bind = Bind::new("slot name", "slot kind", (2, 1, 1));
+	/// bind.connect(((0, 0, 0), (1, 1, 1)), "old/data/0");
+	/// bind.connect(((1, 0, 0), (1, 1, 1)), "older");
+	///
+	/// bind.rename_target("old", "new");
+	/// assert_eq!(bind.targets(), vec!["new/data/0", "older"]);
+	/// ```
+	pub fn rename_target(&mut self, old: &str, new: &str) {
+		for map in &mut self.maps {
+			rename_path_scheme(&mut map.target, old, new);
+		}
+	}
+
+	/// Returns targets of all the connections of the bind in order of
+	/// addition.
+	pub fn targets(&self) -> Vec<&str> {
+		self.maps.iter()
+			.map(|map| map.target.as_str())
+			.collect()
+	}
+
+	/// Moves all the connections and sectors of the bind by `shift`.
+	/// Connections, which completely leave bounds of the bind, are
+	/// dropped, partially left ones only connect points inside of the
+	/// bounds. Sectors, which do not fit into the bounds after the
+	/// shift, are dropped.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::bind::Bind;
+	/// # use crate::sm_logic::util::Point;
+	/// let mut bind = Bind::new("slot name", "slot kind", (4, 1, 1));
+	/// bind.connect(((0, 0, 0), (2, 1, 1)), "low");
+	/// bind.connect(((2, 0, 0), (2, 1, 1)), "high");
+	/// bind.add_sector("low", (0, 0, 0), (2, 1, 1), "binary").unwrap();
+	///
+	/// bind.offset(Point::new_ng(2, 0, 0));
+	/// assert_eq!(bind.targets(), vec!["low"]);
+	/// ```
+	pub fn offset(&mut self, shift: Point) -> &mut Self {
+		let size = self.size;
+		let overlaps = |corner: Point, bounds: Bounds| {
+			(0..3).all(|axis| corner[axis] < size[axis] as i32 && corner[axis] + bounds[axis] as i32 > 0)
+		};
+
+		self.maps.retain_mut(|map| {
+			map.sector_corner += shift;
+			overlaps(map.sector_corner, map.sector_size)
+		});
+
+		self.sectors.retain_mut(|(_, corner, bounds, _)| {
+			*corner += shift;
+			let end = *corner + bounds.cast::<i32>();
+			is_point_in_bounds(*corner, size) && is_point_in_bounds(end, size + 1)
+		});
+
+		self
+	}
+
 	/// Connects some part (sector) of the slot with custom connection (`conn` argument)
 	///
 	/// # Example
@@ -235,6 +300,30 @@ impl Bind {
 		self.custom(sector, target, ConnStraight::new())
 	}
 
+	/// Connects some part (sector) of the slot to each of the `targets`
+	/// with straight connection (ConnStraight)
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::bind::Bind;
+	/// # let mut bind = Bind::new("slot name", "slot kind", (10, 15, 20));
+	/// bind.connect_iter(((0, 0, 0), (1, 1, 1)), ["first/slot", "second/slot"]);
+	/// // is equal to
+	/// bind.connect(((0, 0, 0), (1, 1, 1)), "first/slot")
+	/// 	.connect(((0, 0, 0), (1, 1, 1)), "second/slot");
+	/// ```
+	pub fn connect_iter<I, P, Pt, B>(&mut self, sector: (Pt, B), targets: I) -> &mut Self
+		where I: IntoIterator<Item = P>, P: Into<String>, Pt: Into<Point>, B: Into<Bounds>
+	{
+		let sector: (Point, Bounds) = (sector.0.into(), sector.1.into());
+
+		for target in targets {
+			self.connect(sector, target);
+		}
+
+		self
+	}
+
 	/// Connects some part (sector) of the slot with [`ConnDim`] connection
 	///
 	/// # Example
@@ -297,6 +386,24 @@ impl Bind {
 		)
 	}
 
+	/// Connects the whole slot to each of the `targets` with straight
+	/// connection (ConnStraight)
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::bind::Bind;
+	/// # let mut bind = Bind::new("slot name", "slot kind", (1, 1, 1));
+	/// bind.connect_full_iter(["and_1", "and_2", "and_3"]);
+	/// // is equal to
+	/// bind.connect_full("and_1")
+	/// 	.connect_full("and_2")
+	/// 	.connect_full("and_3");
+	/// ```
+	pub fn connect_full_iter<I, P>(&mut self, targets: I) -> &mut Self
+		where I: IntoIterator<Item = P>, P: Into<String>
+	{
+		self.connect_iter((Point::new_ng(0, 0, 0), self.size), targets)
+	}
+
 	/// Connects the whole slot with [`ConnDim`] connection
 	/// # Example
 	/// ```
@@ -363,13 +470,21 @@ impl Bind {
 	// 										name, start shape, slots
 	pub fn compile(self, schemes: &HashMap<String, (usize, Vec<Slot>)>)
 		-> (Slot, Vec<InvalidConn>)
+	{
+		self.compile_with_default(schemes, scheme::DEFAULT_SLOT)
+	}
+
+	/// Same as [`Bind::compile`], but targets with empty slot name are
+	/// resolved to `default_slot`.
+	pub fn compile_with_default(self, schemes: &HashMap<String, (usize, Vec<Slot>)>, default_slot: &str)
+		-> (Slot, Vec<InvalidConn>)
 	{
 		let mut map: Map3D<Vec<usize>> = Map3D::filled(self.bounds().cast().tuple(), vec![]);
 		let mut errors: Vec<InvalidConn> = vec![];
 
 		for sector in self.maps {
 			let (start_shape, slot, slot_sector) =
-				match compile_get_slot(&sector, schemes) {
+				match compile_get_slot(&sector, schemes, default_slot) {
 					Err(e) => {
 						errors.push(e);
 						continue;
@@ -415,7 +530,7 @@ impl Bind {
 	}
 }
 
-fn compile_get_slot<'a>(sector: &BasicBind, schemes: &'a HashMap<String, (usize, Vec<Slot>)>)
+fn compile_get_slot<'a>(sector: &BasicBind, schemes: &'a HashMap<String, (usize, Vec<Slot>)>, default_slot: &str)
 	-> Result<(usize, &'a Slot, &'a SlotSector), InvalidConn>
 {
 
@@ -446,7 +561,7 @@ fn compile_get_slot<'a>(sector: &BasicBind, schemes: &'a HashMap<String, (usize,
 			(*start_shape, slots)
 	};
 
-	let slot = match scheme::find_slot(&slot_name, slots) {
+	let slot = match scheme::find_slot_or_default(&slot_name, slots, default_slot) {
 		None => {
 			return Err(InvalidConn::TargetSlotDoesNotExists {
 				sector: sector.clone(),
@@ -471,10 +586,55 @@ fn compile_get_slot<'a>(sector: &BasicBind, schemes: &'a HashMap<String, (usize,
 	}
 }
 
+/// Replaces scheme name `old` in `path` (`"scheme/slot/sector"`) with
+/// `new`. Paths of the other schemes are not changed.
+pub(crate) fn rename_path_scheme(path: &mut String, old: &str, new: &str) {
+	let rest = match path.strip_prefix(old) {
+		Some(rest) if rest.is_empty() || rest.starts_with('/') => rest.to_string(),
+		_ => return,
+	};
+
+	*path = format!("{}{}", new, rest);
+}
+
 #[derive(Debug, Clone)]
 pub struct BasicBind {
 	sector_corner: Point,
 	sector_size: Bounds,
 	target: String,
 	conn: Box<dyn Connection>
-}
\ No newline at end of file
+}
+
+#[test]
+fn offset_test() {
+	use crate::combiner::Combiner;
+	use crate::shape::vanilla::GateMode;
+	use crate::testing::Simulator;
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.add_shapes_cube("gates", (2, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+
+	// Template connects the first two points, stamped onto the last two
+	let mut input = Bind::new("_", "binary", (4, 1, 1));
+	input.connect(((0, 0, 0), (2, 1, 1)), "gates");
+	input.add_sector("pair", (0, 0, 0), (2, 1, 1), "binary").unwrap();
+	input.offset(Point::new_ng(2, 0, 0));
+	combiner.bind_input(input).unwrap();
+	combiner.pass_output("_", "gates", None as Option<String>).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	let slot = &scheme.inputs()[0];
+	assert!(slot.get_point(Point::new_ng(0, 0, 0)).unwrap().is_empty());
+	assert!(slot.get_point(Point::new_ng(1, 0, 0)).unwrap().is_empty());
+	assert_eq!(slot.get_sector(&"pair".to_string()).unwrap().pos, Point::new_ng(2, 0, 0));
+
+	let mut sim = Simulator::new(&scheme);
+	sim.set_input_number("_", 0b0011).unwrap();
+	sim.run(2);
+	assert_eq!(sim.output_number("_").unwrap(), 0);
+
+	sim.set_input_number("_", 0b1000).unwrap();
+	sim.run(2);
+	assert_eq!(sim.output_number("_").unwrap(), 0b10);
+}
@@ -3,6 +3,21 @@ use std::path::PathBuf;
 
 use json::{JsonValue, object};
 use uuid::Uuid;
+use crate::scheme::Scheme;
+
+pub const NO_DESCRIPTION: &str = "#{STEAM_WORKSHOP_NO_DESCRIPTION}";
+
+/// Creates content of `description.json` of the blueprint. `local_id`
+/// is the UUID of the blueprint, which is also the name of its folder.
+pub fn description_json(name: &str, local_id: &str, description: &str) -> JsonValue {
+	object! {
+		"description" : description,
+		"localId" : local_id,
+		"name" : name,
+		"type" : "Blueprint",
+		"version" : 0
+	}
+}
 
 /// Blueprint manager
 pub struct BPManager {
@@ -29,8 +44,22 @@ impl BPManager {
 	pub fn save<S>(&self, name: S, blueprint: JsonValue, overwrite_if_exists: bool) -> io::Result<bool>
 		where S: Into<String>
 	{
-		let name = name.into();
+		self.save_described(name.into(), blueprint, NO_DESCRIPTION.to_string(), overwrite_if_exists)
+	}
+
+	/// Same as [`BPManager::save`], but takes a [`Scheme`] and writes its
+	/// description (see [`Scheme::set_description`]) into
+	/// `description.json` of the blueprint.
+	pub fn save_scheme<S>(&self, name: S, scheme: Scheme, overwrite_if_exists: bool) -> io::Result<bool>
+		where S: Into<String>
+	{
+		let description = scheme.description().clone()
+			.unwrap_or(NO_DESCRIPTION.to_string());
+
+		self.save_described(name.into(), scheme.to_json(), description, overwrite_if_exists)
+	}
 
+	fn save_described(&self, name: String, blueprint: JsonValue, description: String, overwrite_if_exists: bool) -> io::Result<bool> {
 		match self.get_bp_folder(&name) {
 			Some(path) => {
 				if !overwrite_if_exists {
@@ -38,29 +67,23 @@ impl BPManager {
 				}
 				let folder_name = path.file_name().unwrap().to_str().unwrap();
 				println!("Overwriting '{}'", folder_name);
-				self.generate_bp(folder_name.into(), name, blueprint)?;
+				self.generate_bp(folder_name.into(), name, blueprint, description)?;
 				Ok(true)
 			}
 
 			None => {
-				self.generate_bp(Uuid::new_v4().to_string().into(), name, blueprint)?;
+				self.generate_bp(Uuid::new_v4().to_string().into(), name, blueprint, description)?;
 				Ok(true)
 			}
 		}
 	}
 
-	fn generate_bp(&self, folder_name: PathBuf, name: String, bp: JsonValue) -> io::Result<()> {
+	fn generate_bp(&self, folder_name: PathBuf, name: String, bp: JsonValue, description: String) -> io::Result<()> {
 		let blueprint_path = self.folder.join(folder_name.clone()).join("blueprint.json");
 		let blueprint = bp.to_string();
 
 		let description_path = self.folder.join(folder_name.clone()).join("description.json");
-		let description = object! {
-			"description" : "#{STEAM_WORKSHOP_NO_DESCRIPTION}",
-		   "localId" : folder_name.to_str().unwrap(),
-		   "name" : name,
-		   "type" : "Blueprint",
-		   "version" : 0
-		}.to_string();
+		let description = description_json(&name, folder_name.to_str().unwrap(), &description).to_string();
 
 		if !self.folder.join(folder_name.clone()).exists() {
 			std::fs::create_dir(self.folder.join(self.folder.join(folder_name.clone())))?;
@@ -131,13 +154,8 @@ impl BPManager {
 		match self.get_bp_folder(&name) {
 			Some(folder) => {
 				let descr_path = folder.join("description.json");
-				let description = object! {
-					"description" : description,
-				   	"localId" : folder.file_name().unwrap().to_str().unwrap(),
-				   	"name" : name,
-				   	"type" : "Blueprint",
-				   	"version" : 0
-				}.to_string();
+				let local_id = folder.file_name().unwrap().to_str().unwrap();
+				let description = description_json(&name, local_id, &description).to_string();
 
 				std::fs::write(descr_path, description).unwrap();
 
@@ -147,4 +165,26 @@ impl BPManager {
 			None => Err(format!("Blueprint '{}' does not exists", name))
 		}
 	}
-}
\ No newline at end of file
+}
+
+#[test]
+fn save_scheme_description_test() {
+	use crate::presets::math::adder;
+
+	let folder = std::env::temp_dir().join(format!("sm_logic_bp_test_{}", Uuid::new_v4()));
+	std::fs::create_dir_all(&folder).unwrap();
+	let manager = BPManager::from_folder(&folder).unwrap();
+
+	let mut scheme = adder(4);
+	scheme.set_description("4 bit adder");
+	assert_eq!(scheme.description(), &Some("4 bit adder".to_string()));
+	assert!(manager.save_scheme("adder", scheme, false).unwrap());
+
+	let bp_folder = manager.get_bp_folder("adder").unwrap();
+	let description = std::fs::read_to_string(bp_folder.join("description.json")).unwrap();
+	let description = json::parse(&description).unwrap();
+	assert_eq!(description["description"], "4 bit adder");
+	assert_eq!(description["name"], "adder");
+
+	std::fs::remove_dir_all(&folder).unwrap();
+}
@@ -1,15 +1,44 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
-use crate::bind::{Bind, InvalidConn};
+use crate::bind::{Bind, InvalidConn, rename_path_scheme};
 use crate::combiner::Error::{InvalidName, NameWasAlreadyTaken};
-use crate::connection::{ConnDim, Connection, ConnStraight};
+use crate::connection::{ConnDim, ConnFilter, ConnMap, Connection, ConnStraight};
 use crate::positioner::{ManualPos, Positioner};
-use crate::presets::shapes_cube;
+use crate::presets::{shapes_cube, shift_connection};
 use crate::scheme;
 use crate::scheme::Scheme;
 use crate::shape::Shape;
-use crate::slot::{Slot, SlotSector};
-use crate::util::{Bounds, is_point_in_bounds, MAX_CONNECTIONS, Point, Rot, split_first_token};
+use crate::shape::vanilla::{BlockBody, BlockType, Gate, GateMode, Timer};
+use crate::slot::{kinds_compatible, Slot, SlotSector};
+use crate::util::{Axis, Bounds, is_point_in_bounds, MAX_CONNECTIONS, Point, Rot, split_first_token};
+
+/// Post-processing steps of [`Combiner::compile_optimized`]. Steps
+/// are applied in the order of the fields.
+///
+/// Default is the recommended one: duplicate gates are merged and
+/// unused shapes are removed.
+#[derive(Debug, Clone, Copy)]
+pub struct OptimizeOpts {
+	/// Merge duplicate gates with [`Scheme::dedup_gates`].
+	pub dedup_gates: bool,
+	/// Remove shapes, that do not affect outputs, with
+	/// [`Scheme::remove_unused`].
+	pub remove_unused: bool,
+	/// Replace shapes, that do not affect outputs, with given block
+	/// with [`Scheme::replace_unused_with`]. Does nothing, if
+	/// `remove_unused` is set.
+	pub replace_unused_with: Option<BlockType>,
+}
+
+impl Default for OptimizeOpts {
+	fn default() -> Self {
+		OptimizeOpts {
+			dedup_gates: true,
+			remove_unused: true,
+			replace_unused_with: None,
+		}
+	}
+}
 
 /// Container for all invalid actions performed on the Combiner.
 #[derive(Debug, Clone)]
@@ -17,6 +46,10 @@ pub struct InvalidActs {
 	pub connections: Vec<ConnCase>,
 	pub inp_bind_conns: Vec<(String, InvalidConn)>,
 	pub out_bind_conns: Vec<(String, InvalidConn)>,
+	/// Connections between slots of incompatible kinds, with kinds of
+	/// the output and the input. Only filled in strict kinds mode (see
+	/// [`Combiner::strict_kinds`]).
+	pub kind_mismatches: Vec<(ConnCase, String, String)>,
 }
 
 impl InvalidActs {
@@ -25,11 +58,20 @@ impl InvalidActs {
 			connections: vec![],
 			inp_bind_conns: vec![],
 			out_bind_conns: vec![],
+			kind_mismatches: vec![],
 		}
 	}
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Approximate size of the scheme, that combiner will compile into
+/// (see [`Combiner::estimate`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+	pub shapes_count: usize,
+	pub bounds: Option<Bounds>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SlotSide {
 	Input, Output
 }
@@ -54,7 +96,15 @@ pub enum Error {
 
 	NoSuchScheme {
 		name: String,
-	}
+	},
+
+	NoSuchSlot {
+		path: String,
+	},
+
+	NoTargets {
+		tip: String,
+	},
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +115,9 @@ pub enum CompileError<P> {
 		affected_outputs: Vec<String>,
 		tip: String,
 	},
+	SchemesOverlap {
+		pairs: Vec<(String, String)>,
+	},
 }
 
 /// Container for single connection with all of its parameters
@@ -289,6 +342,8 @@ pub struct ConnCase {
 #[derive(Debug, Clone)]
 pub struct Combiner<P: Positioner> {
 	schemes: HashMap<String, Scheme>,
+	// Names of the schemes in order of addition
+	schemes_order: Vec<String>,
 	last_scheme: Option<String>,
 
 	connections: Vec<ConnCase>,
@@ -298,7 +353,14 @@ pub struct Combiner<P: Positioner> {
 	outputs: Vec<Bind>,
 
 	conns_overflow_allowed: bool,
+	conns_limit: u32,
+	auto_split_overflow: bool,
+	check_collisions: bool,
+	strict_connections: bool,
+	strict_kinds: bool,
+	adaptors: HashMap<(String, String), Box<dyn Connection>>,
 	debug_name: Option<String>,
+	default_slot: String,
 }
 
 impl Combiner<ManualPos> {
@@ -313,20 +375,120 @@ impl<P: Positioner> Combiner<P> {
 	pub fn new(positioner: P) -> Self {
 		Combiner {
 			schemes: HashMap::new(),
+			schemes_order: vec![],
 			last_scheme: None,
 			connections: vec![],
 			positioner,
 			inputs: vec![],
 			outputs: vec![],
 			conns_overflow_allowed: false,
+			conns_limit: MAX_CONNECTIONS,
+			auto_split_overflow: false,
+			check_collisions: false,
+			strict_connections: false,
+			strict_kinds: false,
+			adaptors: HashMap::new(),
 			debug_name: None,
+			default_slot: scheme::DEFAULT_SLOT.to_string(),
+		}
+	}
+
+	/// Adds `count.0 * count.1 * count.2` copies of the scheme named
+	/// `"{prefix}_{x}_{y}_{z}"` and places each of them at
+	/// `(x, y, z) * scheme.bounds()` (see [`Positioner::suggest_pos`]),
+	/// so they form a 3D grid. Names of the added schemes are returned
+	/// (Z changes first, then Y, then X).
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// let mut combiner = Combiner::pos_manual();
+	/// let names = combiner.add_grid("cell", GateMode::AND, (2, 1, 3)).unwrap();
+	///
+	/// assert_eq!(names.len(), 6);
+	/// assert_eq!(names[0], "cell_0_0_0");
+	/// assert_eq!(names[1], "cell_0_0_1");
+	///
+	/// let (scheme, _) = combiner.compile().unwrap();
+	/// assert_eq!(scheme.bounds().tuple(), (2, 1, 3));
+	/// ```
+	pub fn add_grid<S: Into<Scheme>>(&mut self, prefix: &str, scheme: S, count: (u32, u32, u32)) -> Result<Vec<String>, Error> {
+		let scheme = scheme.into();
+		let cell_size: Point = scheme.bounds().cast();
+		let mut names = vec![];
+
+		for x in 0..count.0 {
+			for y in 0..count.1 {
+				for z in 0..count.2 {
+					let name = format!("{}_{}_{}_{}", prefix, x, y, z);
+					self.add(&name, scheme.clone())?;
+
+					let pos = Point::new(x as i32, y as i32, z as i32);
+					self.positioner.suggest_pos(&name, pos * cell_size);
+
+					names.push(name);
+				}
+			}
 		}
+
+		Ok(names)
 	}
 
+	/// Sets the name, that is used in error messages. Compiled scheme
+	/// remembers it (see [`Scheme::origin`]), so errors of combiners,
+	/// which use the scheme, mention it too.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::{Combiner, Error};
+	/// let mut inner = Combiner::pos_manual();
+	/// inner.set_debug_name("inner adder");
+	/// inner.add("gate", GateMode::AND).unwrap();
+	/// inner.pos().place_last((0, 0, 0));
+	/// inner.pass_input("a", "gate", None as Option<String>).unwrap();
+	/// let (inner, _) = inner.compile().unwrap();
+	///
+	/// let mut outer = Combiner::pos_manual();
+	/// outer.set_debug_name("outer");
+	/// outer.add("adder", inner).unwrap();
+	///
+	/// match outer.pass_input("b", "adder/b", None as Option<String>) {
+	/// 	Err(Error::PassHasInvalidTarget { tip, .. }) => {
+	/// 		assert!(tip.contains("'outer'"));
+	/// 		assert!(tip.contains("'inner adder'"));
+	/// 	}
+	/// 	_ => panic!("Error expected"),
+	/// }
+	/// ```
 	pub fn set_debug_name<S: Into<String>>(&mut self, name: S) {
 		self.debug_name = Some(name.into());
 	}
 
+	/// Sets the name of the slot, which is used, when a path has empty
+	/// slot name (e.g. `"adder"` or `"adder//sector"`). Affects
+	/// connections, binds and passes of this combiner only. By default
+	/// it is [`scheme::DEFAULT_SLOT`].
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.set_default_slot_name("out");
+	/// assert_eq!(combiner.default_slot_name(), "out");
+	/// ```
+	pub fn set_default_slot_name<S: Into<String>>(&mut self, name: S) {
+		self.default_slot = name.into();
+	}
+
+	/// Returns the name of the slot, which is used, when a path has
+	/// empty slot name. See [`Combiner::set_default_slot_name`].
+	pub fn default_slot_name(&self) -> &str {
+		&self.default_slot
+	}
+
 	/// Returns mutable reference to positioner
 	///
 	/// # Example
@@ -359,6 +521,176 @@ impl<P: Positioner> Combiner<P> {
 	pub fn allow_conns_overflow(&mut self) {
 		self.conns_overflow_allowed = true;
 	}
+
+	/// Sets maximal amount of connections of a single shape, which is
+	/// [`MAX_CONNECTIONS`] by default. Some modded servers allow more.
+	/// Used by compilation overflow check, by
+	/// [`Combiner::auto_split_overflow`] and by [`Combiner::broadcast`].
+	/// Presets still use [`MAX_CONNECTIONS`].
+	///
+	/// Will panic if `limit` is less than 2: splitting connections into
+	/// trees of buffer gates needs at least 2 connections per gate.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add("source", GateMode::OR).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add_shapes_cube("targets", (300, 1, 1), GateMode::AND, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 1));
+	/// combiner.dim("source", "targets", (true, true, true));
+	///
+	/// assert!(combiner.clone().compile().is_err());
+	///
+	/// combiner.set_conns_limit(300);
+	/// assert!(combiner.compile().is_ok());
+	/// ```
+	pub fn set_conns_limit(&mut self, limit: u32) {
+		assert!(limit >= 2, "Connections limit must be at least 2, got {}", limit);
+		self.conns_limit = limit;
+	}
+
+	/// Instead of failing with [`CompileError::ConnectionsOverflow`],
+	/// compilation will split overflowing connections into trees of
+	/// buffer gates, so that no shape has more than [`MAX_CONNECTIONS`]
+	/// (or [`Combiner::set_conns_limit`]) inputs or outputs. Each buffer adds 1 tick of delay to the
+	/// signals, that go through it.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add("source", GateMode::OR).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add_shapes_cube("targets", (300, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 1, 0));
+	///
+	/// combiner.add("sink", GateMode::AND).unwrap();
+	/// combiner.pos().place_last((0, 2, 0));
+	///
+	/// // Single gate is connected to 300 gates, and all of them are
+	/// // connected to another single gate
+	/// combiner.dim("source", "targets", (true, true, true));
+	/// combiner.dim("targets", "sink", (true, true, true));
+	///
+	/// let failing = combiner.clone();
+	/// assert!(failing.compile().is_err());
+	///
+	/// combiner.auto_split_overflow();
+	/// let (scheme, _) = combiner.compile().unwrap();
+	///
+	/// // Two OR buffers for the source and two AND buffers for the sink
+	/// assert_eq!(scheme.shapes_count(), 306);
+	///
+	/// let mut incoming = vec![0; scheme.shapes_count()];
+	/// for (_, _, shape) in scheme.shapes() {
+	/// 	assert!(shape.connections().len() <= 255);
+	/// 	for target in shape.connections() {
+	/// 		incoming[*target] += 1;
+	/// 	}
+	/// }
+	/// assert!(incoming.iter().all(|count| *count <= 255));
+	/// ```
+	pub fn auto_split_overflow(&mut self) {
+		self.auto_split_overflow = true;
+	}
+
+	/// If `strict`, connections, which have both ends resolved, but did
+	/// not connect any pair of points (e.g. sectors do not overlap after
+	/// the shift), are reported in [`InvalidActs::connections`] after
+	/// compilation. Such connection is most likely a mistake.
+	pub fn set_strict_connections(&mut self, strict: bool) {
+		self.strict_connections = strict;
+	}
+
+	/// Connections between slots of incompatible kinds (see
+	/// [`kinds_compatible`]), which have no registered adaptor, will be
+	/// reported in [`InvalidActs::kind_mismatches`] after compilation.
+	/// Such connections are still made.
+	pub fn strict_kinds(&mut self) {
+		self.strict_kinds = true;
+	}
+
+	/// Registers the connection, which is chained before the given
+	/// one, when output of `from_kind` is connected to input of
+	/// incompatible `to_kind`. Kinds are taken from the connected slot
+	/// sectors. Adaptor converts the output into the layout of the
+	/// input (virtual slot has bounds of the input), then the given
+	/// connection is applied as if both sides had the same kind. So
+	/// plain [`Combiner::connect`] gives just the adaptor.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::connection::ConnMap;
+	/// # use crate::sm_logic::presets::convertors::bin_to_gray;
+	/// # use crate::sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::util::Point;
+	/// // Number with reversed order of bits
+	/// let reversed = {
+	/// 	let mut inner = Combiner::pos_manual();
+	/// 	inner.add_shapes_cube("gates", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// 	inner.pos().place_last((0, 0, 0));
+	/// 	inner.pass_output("_", "gates", Some("reversed_binary")).unwrap();
+	/// 	inner.compile().unwrap().0
+	/// };
+	///
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.strict_kinds();
+	/// combiner.add("source", reversed).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add("target", bin_to_gray(4)).unwrap();
+	/// combiner.pos().place_last((0, 0, 5));
+	/// combiner.connect("source", "target");
+	///
+	/// let (_, invalid) = combiner.clone().compile().unwrap();
+	/// assert_eq!(invalid.kind_mismatches.len(), 1);
+	///
+	/// combiner.register_adaptor("reversed_binary", "binary", ConnMap::new(|(point, in_bounds), _| {
+	/// 	Some(Point::new(*in_bounds.x() as i32 - *point.x() - 1, 0, 0))
+	/// }));
+	/// let (_, invalid) = combiner.compile().unwrap();
+	/// assert!(invalid.kind_mismatches.is_empty());
+	/// ```
+	pub fn register_adaptor<F, T>(&mut self, from_kind: F, to_kind: T, adaptor: Box<dyn Connection>)
+		where F: Into<String>, T: Into<String>
+	{
+		self.adaptors.insert((from_kind.into(), to_kind.into()), adaptor);
+	}
+
+	/// Compilation will fail with [`CompileError::SchemesOverlap`] if
+	/// bounding boxes of some of the placed schemes intersect.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::{Combiner, CompileError};
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.check_collisions();
+	/// combiner.add_shapes_cube("row_0", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// // Forgot to offset the next row
+	/// combiner.add_shapes_cube("row_1", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((3, 0, 0));
+	/// combiner.add_shapes_cube("row_2", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 1, 0));
+	/// // Rotated column is placed right after "row_1"
+	/// combiner.add_shapes_cube("column", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((7, 0, 0));
+	/// combiner.pos().rotate_last((0, 0, 1));
+	///
+	/// match combiner.compile() {
+	/// 	Err(CompileError::SchemesOverlap { pairs }) =>
+	/// 		assert_eq!(pairs, vec![("row_0".to_string(), "row_1".to_string())]),
+	/// 	_ => panic!("Overlap expected"),
+	/// }
+	/// ```
+	pub fn check_collisions(&mut self) {
+		self.check_collisions = true;
+	}
 }
 
 impl<P: Positioner> Combiner<P> {
@@ -393,6 +725,72 @@ impl<P: Positioner> Combiner<P> {
 	}
 }
 
+impl<P: Positioner> Combiner<P> {
+	/// Paints all the shapes of the inner scheme with given name. Only
+	/// shapes with default color are painted (see [`Scheme::soft_paint`]).
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add("gate", GateMode::AND).unwrap();
+	///
+	/// assert!(combiner.paint("gate", "00ff00").is_ok());
+	/// assert!(combiner.paint("no such scheme", "00ff00").is_err());
+	/// ```
+	pub fn paint<N, S>(&mut self, name: N, color: S) -> Result<(), Error>
+		where N: Into<String>,
+			  S: Into<String>
+	{
+		let name = name.into();
+
+		match self.schemes.get_mut(&name) {
+			Some(scheme) => {
+				scheme.soft_paint(color);
+				Ok(())
+			}
+
+			None => Err(Error::NoSuchScheme { name })
+		}
+	}
+
+	/// Paints only the shapes of inner scheme, that are referenced by
+	/// the slot with given path (`"<scheme>/<slot>/<optional sector>"`).
+	/// Slot can be either input or output.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::presets::math::adder;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add("adder", adder(8)).unwrap();
+	///
+	/// assert!(combiner.paint_slot("adder/a", "ff0000").is_ok());
+	/// assert!(combiner.paint_slot("adder/no such slot", "ff0000").is_err());
+	/// assert!(combiner.paint_slot("no such scheme/a", "ff0000").is_err());
+	/// ```
+	pub fn paint_slot<Pt, S>(&mut self, path: Pt, color: S) -> Result<(), Error>
+		where Pt: Into<String>,
+			  S: Into<String>
+	{
+		let path = path.into();
+		let (scheme_name, slot_name) = self.resolve_slot_path(&path);
+
+		let scheme = match self.schemes.get_mut(&scheme_name) {
+			None => return Err(Error::NoSuchScheme { name: scheme_name }),
+			Some(scheme) => scheme,
+		};
+
+		if scheme.paint_slot(slot_name, color) {
+			Ok(())
+		} else {
+			Err(Error::NoSuchSlot { path })
+		}
+	}
+}
+
 impl<P: Positioner> Combiner<P> {
 	/// Adds scheme with its unique name to the combiner.
 	///
@@ -424,6 +822,7 @@ impl<P: Positioner> Combiner<P> {
 
 		if self.schemes.get(&name).is_none() {
 			self.schemes.insert(name.clone(), scheme.into());
+			self.schemes_order.push(name.clone());
 			self.last_scheme = Some(name.clone());
 			self.pos().set_last_scheme(name);
 			Ok(())
@@ -467,6 +866,65 @@ impl<P: Positioner> Combiner<P> {
 		Ok(())
 	}
 
+	/// Adds the scheme and passes only some of its inputs and outputs.
+	/// For every slot `inputs` and `outputs` return either `None` (slot
+	/// is not passed), or new name of the slot with optional new kind
+	/// (`None` keeps the kind of the slot).
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::presets::binary_selector_compact;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// let mut combiner = Combiner::pos_manual();
+	///
+	/// // Passes selector input as "address", and only even lines
+	/// combiner.add_pass_filtered(
+	/// 	"selector",
+	/// 	binary_selector_compact(3),
+	/// 	|_slot| Some(("address".to_string(), Some("binary".to_string()))),
+	/// 	|slot| match slot.name().parse::<u32>() {
+	/// 		Ok(line) if line % 2 == 0 => Some((format!("line_{}", line), None)),
+	/// 		_ => None,
+	/// 	},
+	/// ).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	///
+	/// let (scheme, _) = combiner.compile().unwrap();
+	/// assert_eq!(scheme.inputs().len(), 1);
+	/// assert_eq!(scheme.outputs().len(), 4);
+	/// assert!(scheme.output("line_6").is_some());
+	/// ```
+	pub fn add_pass_filtered<N, S, I, O>(&mut self, name: N, scheme: S, inputs: I, outputs: O) -> Result<(), Error>
+		where N: Into<String>,
+			  S: Into<Scheme>,
+			  I: Fn(&Slot) -> Option<(String, Option<String>)>,
+			  O: Fn(&Slot) -> Option<(String, Option<String>)>,
+	{
+		let name = name.into();
+		let scheme = scheme.into();
+
+		// bind name, target name, new kind
+		let passed_inputs: Vec<(String, String, Option<String>)> = scheme.inputs().iter()
+			.filter_map(|slot| inputs(slot).map(|(bind_name, kind)| (bind_name, slot.name().clone(), kind)))
+			.collect();
+
+		let passed_outputs: Vec<(String, String, Option<String>)> = scheme.outputs().iter()
+			.filter_map(|slot| outputs(slot).map(|(bind_name, kind)| (bind_name, slot.name().clone(), kind)))
+			.collect();
+
+		self.add(&name, scheme)?;
+
+		for (bind_name, target_name, kind) in passed_inputs {
+			self.pass_input(bind_name, format!("{}/{}", &name, target_name), kind)?;
+		}
+
+		for (bind_name, target_name, kind) in passed_outputs {
+			self.pass_output(bind_name, format!("{}/{}", &name, target_name), kind)?;
+		}
+
+		Ok(())
+	}
+
 	/// Adds all the (name, scheme) pairs passed to the combiner.
 	///
 	/// # Example
@@ -539,6 +997,74 @@ impl<P: Positioner> Combiner<P> {
 		}
 	}
 
+	/// Renames added scheme. All the connections and binds, that
+	/// reference the scheme, and its placement in the positioner are
+	/// updated too.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add("temp", GateMode::AND).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add("output", GateMode::OR).unwrap();
+	/// combiner.pos().place_last((1, 0, 0));
+	/// combiner.connect("temp", "output");
+	/// combiner.pass_input("_", "temp", None as Option<String>).unwrap();
+	///
+	/// combiner.rename("temp", "input").unwrap();
+	/// assert!(combiner.rename("no such scheme", "a").is_err());
+	/// assert!(combiner.rename("input", "output").is_err());
+	/// combiner.rename("input", "input").unwrap();
+	///
+	/// assert_eq!(combiner.connections_touching("input"), vec![0]);
+	/// let (_, invalid_acts) = combiner.compile().unwrap();
+	/// assert_eq!(invalid_acts.inp_bind_conns.len(), 0);
+	/// ```
+	pub fn rename(&mut self, old: &str, new: &str) -> Result<(), Error> {
+		if !self.schemes.contains_key(old) {
+			return Err(Error::NoSuchScheme { name: old.to_string() });
+		}
+
+		if old == new {
+			return Ok(());
+		}
+
+		check_name_validity(&new.to_string())?;
+
+		if self.schemes.contains_key(new) {
+			return Err(NameWasAlreadyTaken {
+				taken_name: new.to_string(),
+				tip: "Cannot rename scheme to the name of another scheme".to_string(),
+			});
+		}
+
+		let scheme = self.schemes.remove(old).unwrap();
+		self.schemes.insert(new.to_string(), scheme);
+
+		for name in &mut self.schemes_order {
+			if name == old {
+				*name = new.to_string();
+			}
+		}
+
+		if self.last_scheme.as_deref() == Some(old) {
+			self.last_scheme = Some(new.to_string());
+		}
+		self.positioner.rename_scheme(old, new);
+
+		for case in &mut self.connections {
+			rename_path_scheme(&mut case.from, old, new);
+			rename_path_scheme(&mut case.to, old, new);
+		}
+
+		for bind in self.inputs.iter_mut().chain(self.outputs.iter_mut()) {
+			bind.rename_target(old, new);
+		}
+
+		Ok(())
+	}
 
 	pub fn line<N, S>(&mut self, name: N, shape: S, length: u32) -> Result<(), Error>
 		where S: Into<Shape>, N: Into<String>
@@ -713,63 +1239,282 @@ impl<P: Positioner> Combiner<P> {
 		self.custom(from, to, ConnDim::new(adapt_axes))
 	}
 
-	/// Just like 'custom', but for multiple targets. ***Each*** slot
-	/// on the left will be connected to ***each*** slot on the right<br>
-	/// (with given connection (`conn` arg)).
-	/// 'custom' - is for 'custom connection'
+	/// Connects two slots with `base` connection, filtered by `function`
+	/// ([`ConnFilter`]). Only point-to-point connections, for which
+	/// `function(start, end)` is true, are kept.
 	///
 	/// # Example
 	/// ```
-	/// # use sm_logic::connection::{ConnStraight};
+	/// # use sm_logic::connection::{ConnFilter, ConnStraight};
 	/// # use crate::sm_logic::combiner::Combiner;
 	/// # let mut combiner = Combiner::pos_manual();
 	///
-	/// combiner.custom_iter(["1", "2", "3"], ["4", "5", "6"], ConnStraight::new());
-	/// // These two ways do the same thing
-	///
-	/// // Each to each
- 	///	let conn = ConnStraight::new();
-	/// combiner.custom("1", "4", conn.clone());
-	/// combiner.custom("1", "5", conn.clone());
-	/// combiner.custom("1", "6", conn.clone());
-	/// combiner.custom("2", "4", conn.clone());
-	/// combiner.custom("2", "5", conn.clone());
-	/// combiner.custom("2", "6", conn.clone());
-	/// combiner.custom("3", "4", conn.clone());
-	/// combiner.custom("3", "5", conn.clone());
-	/// combiner.custom("3", "6", conn.clone());
+	/// // Connects only even bits
+	/// combiner.filter("scheme1/slot1", "scheme2/slot2", ConnStraight::new(), |start, _| start.x() % 2 == 0);
+	/// // These two lines do the same thing
+	/// combiner.custom("scheme1/slot1", "scheme2/slot2", ConnFilter::new(ConnStraight::new(), |start, _| start.x() % 2 == 0));
 	/// ```
-	pub fn custom_iter<I1, I2, P1, P2>(&mut self, from: I1, to: I2, conn: Box<dyn Connection>)
-		where P1: Into<String>, I1: IntoIterator<Item = P1>,
-			  P2: Into<String>, I2: IntoIterator<Item = P2>,
+	pub fn filter<P1, P2, F>(&mut self, from: P1, to: P2, base: Box<dyn Connection>, function: F)
+		where P1: Into<String>,
+			  P2: Into<String>,
+			  F: Fn(&Point, &Point) -> bool + 'static
 	{
-		let to: Vec<String> = to.into_iter()
-			.map(|x| x.into())
-			.collect();
-
-		for from_path in from {
-			let from_path = from_path.into();
-			for to_path in &to {
-				self.custom(from_path.clone(), to_path, conn.clone())
-			}
-		}
+		self.custom(from, to, ConnFilter::new(base, function))
 	}
 
-	/// Just like 'connect', but for multiple targets. ***Each*** slot
-	/// on the left will be connected to ***each*** slot on the right
-	/// with straight connection ([`ConnStraight`]).
-	/// 'Straight' means, that each point of output slot connects to the
-	/// same point of input slot.
+	/// Connects each point of `from` slot to the point of `to` slot
+	/// given by `function` ([`ConnMap`]). Function arguments are
+	/// `(start point, start bounds), end bounds`.
 	///
 	/// # Example
 	/// ```
-	/// # use sm_logic::connection::{ConnStraight};
+	/// # use sm_logic::connection::ConnMap;
 	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::util::Point;
 	/// # let mut combiner = Combiner::pos_manual();
 	///
-	/// combiner.connect_iter(["1", "2", "3"], ["4", "5", "6"]);
+	/// // Shifts bits by one
+	/// combiner.map("scheme1/slot1", "scheme2/slot2", |(point, _), _| Some(point + Point::new_ng(1, 0, 0)));
 	/// // These two lines do the same thing
-	/// combiner.custom_iter(["1", "2", "3"], ["4", "5", "6"], ConnStraight::new());
+	/// combiner.custom("scheme1/slot1", "scheme2/slot2", ConnMap::new(|(point, _), _| Some(point + Point::new_ng(1, 0, 0))));
+	/// ```
+	pub fn map<P1, P2, F>(&mut self, from: P1, to: P2, function: F)
+		where P1: Into<String>,
+			  P2: Into<String>,
+			  F: Fn((Point, Bounds), Bounds) -> Option<Point> + 'static
+	{
+		self.custom(from, to, ConnMap::new(function))
+	}
+
+	/// Connects each of the `sources` (pairs of path and width) to the
+	/// `target` one after another along X axis: first source goes to the
+	/// bits `0..width_0` of the target, second one - to the bits
+	/// `width_0..width_0 + width_1` and so on. Returns total width.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::testing::Simulator;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_shapes_cube("low", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add_shapes_cube("high", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((4, 0, 0));
+	/// combiner.add_shapes_cube("word", (8, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 1));
+	///
+	/// let width = combiner.connect_concat([("low", 4), ("high", 4)], "word");
+	/// assert_eq!(width, 8);
+	///
+	/// combiner.pass_input("low", "low", None as Option<String>).unwrap();
+	/// combiner.pass_input("high", "high", None as Option<String>).unwrap();
+	/// combiner.pass_output("_", "word", None as Option<String>).unwrap();
+	/// let (scheme, _) = combiner.compile().unwrap();
+	///
+	/// let mut sim = Simulator::new(&scheme);
+	/// sim.set_input_number("low", 0x5).unwrap();
+	/// sim.set_input_number("high", 0xA).unwrap();
+	/// sim.run(2);
+	/// assert_eq!(sim.output_number("_").unwrap(), 0xA5);
+	/// ```
+	pub fn connect_concat<I, S>(&mut self, sources: I, target: &str) -> u32
+		where I: IntoIterator<Item = (S, u32)>,
+			  S: Into<String>
+	{
+		let mut offset = 0;
+
+		for (source, width) in sources {
+			self.custom(source, target, shift_connection((offset as i32, 0, 0)));
+			offset += width;
+		}
+
+		offset
+	}
+
+	/// Connects each output slot of `from_scheme` to the input slot of
+	/// `to_scheme` with the same name (straight connection). Slots
+	/// without a pair are skipped. Returns names of the connected slots.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::presets::math::{adder, bitwise};
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add("logic", bitwise(4)).unwrap();
+	/// combiner.add("sum", adder(4)).unwrap();
+	///
+	/// // "sum" has no input named "and", "or", "xor" or "not_a"
+	/// assert_eq!(combiner.connect_matching("logic", "sum").unwrap(), Vec::<String>::new());
+	/// // "logic" has inputs "a" and "b", "sum" has outputs "_" and "carry"
+	/// assert_eq!(combiner.connect_matching("sum", "logic").unwrap(), Vec::<String>::new());
+	///
+	/// combiner.add("next_sum", adder(4)).unwrap();
+	/// // Output "carry" of "sum" goes to input "carry" of "next_sum"
+	/// assert_eq!(combiner.connect_matching("sum", "next_sum").unwrap(), vec!["carry"]);
+	/// assert!(combiner.connect_matching("sum", "no such scheme").is_err());
+	/// ```
+	pub fn connect_matching(&mut self, from_scheme: &str, to_scheme: &str) -> Result<Vec<String>, Error> {
+		let from = self.schemes.get(from_scheme)
+			.ok_or(Error::NoSuchScheme { name: from_scheme.to_string() })?;
+		let to = self.schemes.get(to_scheme)
+			.ok_or(Error::NoSuchScheme { name: to_scheme.to_string() })?;
+
+		let matching: Vec<String> = from.outputs().iter()
+			.map(|slot| slot.name().clone())
+			.filter(|name| to.inputs().iter().any(|slot| slot.name() == name))
+			.collect();
+
+		for name in &matching {
+			self.connect(
+				format!("{}/{}", from_scheme, name),
+				format!("{}/{}", to_scheme, name)
+			);
+		}
+
+		Ok(matching)
+	}
+
+	/// Just like [`Combiner::connect`], but checks, that both sectors
+	/// (`"scheme/slot/sector"`) exist. If sizes of the sectors differ,
+	/// only the smallest part of them is connected.
+	///
+	/// Returns [`Error::NoSuchScheme`] or [`Error::NoSuchSlot`] if any of
+	/// the sectors was not found, nothing is connected in that case.
+	pub fn connect_sector<P1, P2>(&mut self, from: P1, to: P2) -> Result<(), Error>
+		where P1: Into<String>,
+			  P2: Into<String>
+	{
+		let from = from.into();
+		let to = to.into();
+
+		self.find_sector(&from, SlotSide::Output)?;
+		self.find_sector(&to, SlotSide::Input)?;
+		self.connect(from, to);
+
+		Ok(())
+	}
+
+	/// Returns indices of all added connections, which go from or into
+	/// the given slot (`"scheme"`, `"scheme/slot"` or
+	/// `"scheme/slot/sector"`). If sector is not specified, connections
+	/// to any sector of the slot are counted.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # let mut combiner = Combiner::pos_manual();
+	/// combiner.connect("a", "b/data");
+	/// combiner.connect("b/data/0", "c");
+	/// combiner.connect("c/_", "a/_");
+	/// combiner.connect("b/clock", "a");
+	///
+	/// assert_eq!(combiner.connections_touching("b/data"), vec![0, 1]);
+	/// assert_eq!(combiner.connections_touching("b/data/0"), vec![1]);
+	/// // "a" is the same as "a/_"
+	/// assert_eq!(combiner.connections_touching("a"), vec![0, 2, 3]);
+	/// assert_eq!(combiner.connections_touching("d"), Vec::<usize>::new());
+	/// ```
+	pub fn connections_touching(&self, path: &str) -> Vec<usize> {
+		let (scheme, slot, sector) = split_slot_path(path, &self.default_slot);
+
+		let touches = |conn_path: &String| {
+			let (conn_scheme, conn_slot, conn_sector) = split_slot_path(conn_path, &self.default_slot);
+
+			conn_scheme == scheme && conn_slot == slot &&
+				(sector.is_none() || sector == Some(conn_sector.unwrap_or_default()))
+		};
+
+		self.connections.iter()
+			.enumerate()
+			.filter(|(_, conn)| touches(&conn.from) || touches(&conn.to))
+			.map(|(i, _)| i)
+			.collect()
+	}
+
+	/// Removes all the added connections, which go exactly from `from`
+	/// to `to` (paths are compared as is, so `"a"` and `"a/_"` are
+	/// different paths). Returns amount of removed connections.
+	///
+	/// Only connections of this combiner are affected: connections,
+	/// which are already inside added schemes, cannot be removed.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # let mut combiner = Combiner::pos_manual();
+	/// combiner.connect("a", "b");
+	/// combiner.dim("a", "b", (true, false, false));
+	/// combiner.connect("b", "a");
+	///
+	/// assert_eq!(combiner.disconnect("a", "b"), 2);
+	/// assert_eq!(combiner.disconnect("a", "b"), 0);
+	/// assert_eq!(combiner.connections_touching("a"), vec![0]);
+	/// ```
+	pub fn disconnect(&mut self, from: &str, to: &str) -> usize {
+		let count = self.connections.len();
+		self.connections.retain(|case| case.from != from || case.to != to);
+		count - self.connections.len()
+	}
+
+	/// Just like 'custom', but for multiple targets. ***Each*** slot
+	/// on the left will be connected to ***each*** slot on the right<br>
+	/// (with given connection (`conn` arg)).
+	/// 'custom' - is for 'custom connection'
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::connection::{ConnStraight};
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # let mut combiner = Combiner::pos_manual();
+	///
+	/// combiner.custom_iter(["1", "2", "3"], ["4", "5", "6"], ConnStraight::new());
+	/// // These two ways do the same thing
+	///
+	/// // Each to each
+ 	///	let conn = ConnStraight::new();
+	/// combiner.custom("1", "4", conn.clone());
+	/// combiner.custom("1", "5", conn.clone());
+	/// combiner.custom("1", "6", conn.clone());
+	/// combiner.custom("2", "4", conn.clone());
+	/// combiner.custom("2", "5", conn.clone());
+	/// combiner.custom("2", "6", conn.clone());
+	/// combiner.custom("3", "4", conn.clone());
+	/// combiner.custom("3", "5", conn.clone());
+	/// combiner.custom("3", "6", conn.clone());
+	/// ```
+	pub fn custom_iter<I1, I2, P1, P2>(&mut self, from: I1, to: I2, conn: Box<dyn Connection>)
+		where P1: Into<String>, I1: IntoIterator<Item = P1>,
+			  P2: Into<String>, I2: IntoIterator<Item = P2>,
+	{
+		let to: Vec<String> = to.into_iter()
+			.map(|x| x.into())
+			.collect();
+
+		for from_path in from {
+			let from_path = from_path.into();
+			for to_path in &to {
+				self.custom(from_path.clone(), to_path, conn.clone())
+			}
+		}
+	}
+
+	/// Just like 'connect', but for multiple targets. ***Each*** slot
+	/// on the left will be connected to ***each*** slot on the right
+	/// with straight connection ([`ConnStraight`]).
+	/// 'Straight' means, that each point of output slot connects to the
+	/// same point of input slot.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::connection::{ConnStraight};
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # let mut combiner = Combiner::pos_manual();
+	///
+	/// combiner.connect_iter(["1", "2", "3"], ["4", "5", "6"]);
+	/// // These two lines do the same thing
+	/// combiner.custom_iter(["1", "2", "3"], ["4", "5", "6"], ConnStraight::new());
 	/// ```
 	pub fn connect_iter<I1, I2, P1, P2>(&mut self, from: I1, to: I2)
 		where P1: Into<String>, I1: IntoIterator<Item = P1>,
@@ -810,6 +1555,335 @@ impl<P: Positioner> Combiner<P> {
 	{
 		self.custom_iter(from, to, ConnDim::new(adapt_axes))
 	}
+
+	/// Clock gating: adds AND gate with name `name` and connects both
+	/// `clock_src` and `enable_src` into it. So the gate passes clock
+	/// signal only while enable signal is active. Returns the name of
+	/// the gate.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::util::Point;
+	/// let mut combiner = Combiner::pos_manual();
+	///
+	/// combiner.add("clock", GateMode::OR).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add("enable", GateMode::OR).unwrap();
+	/// combiner.pos().place_last((1, 0, 0));
+	///
+	/// let gated = combiner.gate_clock("clock", "enable", "gated_clock").unwrap();
+	/// assert_eq!(gated, "gated_clock");
+	/// combiner.pos().place_last((2, 0, 0));
+	/// combiner.pass_output("_", gated, None as Option<String>).unwrap();
+	///
+	/// let (scheme, invalid_acts) = combiner.compile().unwrap();
+	/// assert_eq!(invalid_acts.connections.len(), 0);
+	/// assert_eq!(scheme.shapes_count(), 3);
+	///
+	/// // Both the clock and the enable gates are connected to the AND gate
+	/// let (slot, _) = scheme.output("_").unwrap();
+	/// let gate_id = slot.get_point(Point::new_ng(0, 0, 0)).unwrap()[0];
+	/// let sources = scheme.shapes().iter()
+	/// 	.filter(|(_, _, shape)| shape.connections().contains(&gate_id))
+	/// 	.count();
+	/// assert_eq!(sources, 2);
+	/// ```
+	pub fn gate_clock<C, E, N>(&mut self, clock_src: C, enable_src: E, name: N) -> Result<String, Error>
+		where C: Into<String>,
+			  E: Into<String>,
+			  N: Into<String>
+	{
+		let name = name.into();
+
+		self.add(&name, GateMode::AND)?;
+		self.connect(clock_src, &name);
+		self.connect(enable_src, &name);
+
+		Ok(name)
+	}
+
+	/// Connects single-point `source` (enable, clock, etc.) to every point
+	/// of the `bus` input slot. If the bus has more than
+	/// [`MAX_CONNECTIONS`] points, a line of OR gates is added to repeat
+	/// the signal (which adds 1 tick of delay). Name of the repeaters
+	/// scheme (`broadcast_0`, `broadcast_1`, etc.) is returned, it
+	/// becomes the last added scheme, so it can be placed with the
+	/// positioner right after the call.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::testing::Simulator;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add("enable", GateMode::OR).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add_shapes_cube("bus", (300, 1, 1), GateMode::AND, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 2));
+	///
+	/// let repeaters = combiner.broadcast("enable", "bus").unwrap();
+	/// assert_eq!(repeaters, Some("broadcast_0".to_string()));
+	/// combiner.pos().place_last((0, 0, 1));
+	///
+	/// combiner.pass_input("enable", "enable", None as Option<String>).unwrap();
+	/// combiner.pass_output("_", "bus", None as Option<String>).unwrap();
+	/// // No connections overflow
+	/// let (scheme, _) = combiner.compile().unwrap();
+	///
+	/// let mut sim = Simulator::new(&scheme);
+	/// sim.set_input("enable", &[true]).unwrap();
+	/// sim.run(3);
+	/// assert!(sim.output("_").unwrap().iter().all(|bit| *bit));
+	/// ```
+	pub fn broadcast(&mut self, source: &str, bus: &str) -> Result<Option<String>, Error> {
+		let bounds = self.find_sector(bus, SlotSide::Input)?.bounds;
+
+		let limit = self.conns_limit;
+		let points_count = bounds.x() * bounds.y() * bounds.z();
+		if points_count <= limit {
+			self.dim(source, bus, (true, true, true));
+			return Ok(None);
+		}
+
+		let name = (0..)
+			.map(|i| format!("broadcast_{}", i))
+			.find(|name| !self.schemes.contains_key(name))
+			.unwrap();
+
+		let repeaters_count = points_count.div_ceil(limit);
+		self.add_shapes_cube(&name, (repeaters_count, 1, 1), GateMode::OR, Rot::new(0, 0, 0))?;
+		self.dim(source, &name, (true, true, true));
+
+		// Each repeater is connected to its own chunk of the bus points
+		let (size_x, size_y) = (*bounds.x() as i32, *bounds.y() as i32);
+		self.filter(&name, bus, ConnDim::new((true, true, true)), move |start, end| {
+			let end_id = end.x() + end.y() * size_x + end.z() * size_x * size_y;
+			end_id / (limit as i32) == *start.x()
+		});
+
+		Ok(Some(name))
+	}
+
+	/// Connects `width` points of `from` slot, starting at `from_start`,
+	/// to points of `to` slot, starting at `to_start`, along X axis.
+	/// Useful to connect only a part of a bus. See
+	/// [`Combiner::connect_range_axis`] for other axes.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::testing::Simulator;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_shapes_cube("a", (8, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add_shapes_cube("b", (5, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 1));
+	///
+	/// // Bits 3..8 of "a" go to bits 0..5 of "b"
+	/// combiner.connect_range("a", 3, "b", 0, 5);
+	///
+	/// combiner.pass_input("_", "a", None as Option<String>).unwrap();
+	/// combiner.pass_output("_", "b", None as Option<String>).unwrap();
+	/// let (scheme, _) = combiner.compile().unwrap();
+	///
+	/// let mut sim = Simulator::new(&scheme);
+	/// sim.set_input_number("_", 0b1011_0110).unwrap();
+	/// sim.run(2);
+	/// assert_eq!(sim.output_number("_").unwrap(), 0b10110);
+	/// ```
+	pub fn connect_range<P1, P2>(&mut self, from: P1, from_start: u32, to: P2, to_start: u32, width: u32)
+		where P1: Into<String>,
+			  P2: Into<String>
+	{
+		self.connect_range_axis(from, from_start, to, to_start, width, Axis::X)
+	}
+
+	/// Same as [`Combiner::connect_range`], but ranges are taken along
+	/// given `axis`. Other coordinates of points stay the same.
+	pub fn connect_range_axis<P1, P2>(&mut self, from: P1, from_start: u32, to: P2, to_start: u32, width: u32, axis: Axis)
+		where P1: Into<String>,
+			  P2: Into<String>
+	{
+		let axis = match axis {
+			Axis::X => 0,
+			Axis::Y => 1,
+			Axis::Z => 2,
+		};
+		let (from_start, to_start) = (from_start as i32, to_start as i32);
+
+		let connection = ConnMap::new(move |(point, _), _| {
+			let coord = point[axis];
+			if coord < from_start || coord >= from_start + width as i32 {
+				return None;
+			}
+
+			let mut target = point;
+			target[axis] = coord - from_start + to_start;
+			Some(target)
+		});
+
+		self.custom(from, to, connection)
+	}
+
+	/// Connects `from` output slot to `to` input slot through a cube of
+	/// timers of the `from` slot size. Timers are configured to `ticks`
+	/// ticks, and as any logic part need one more tick to pass the
+	/// signal. Timers scheme gets unique name (`delay_0`, `delay_1`, etc.),
+	/// which is returned. It becomes the last added scheme, so it can
+	/// be placed with the positioner right after the call.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::testing::Simulator;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_shapes_cube("a", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add_shapes_cube("b", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 3));
+	///
+	/// let timers = combiner.connect_delayed("a", "b", 5).unwrap();
+	/// assert_eq!(timers, "delay_0");
+	/// combiner.pos().place_last((0, 0, 1));
+	///
+	/// combiner.pass_input("_", "a", None as Option<String>).unwrap();
+	/// combiner.pass_output("_", "b", None as Option<String>).unwrap();
+	/// let (scheme, invalid_acts) = combiner.compile().unwrap();
+	/// assert_eq!(invalid_acts.connections.len(), 0);
+	///
+	/// let mut sim = Simulator::new(&scheme);
+	/// sim.set_input_number("_", 0b1011).unwrap();
+	/// // 1 tick for "a", 6 ticks for the timers, 1 tick for "b"
+	/// sim.run(7);
+	/// assert_eq!(sim.output_number("_").unwrap(), 0);
+	/// sim.run(1);
+	/// assert_eq!(sim.output_number("_").unwrap(), 0b1011);
+	/// ```
+	pub fn connect_delayed<P1, P2>(&mut self, from: P1, to: P2, ticks: u32) -> Result<String, Error>
+		where P1: Into<String>,
+			  P2: Into<String>
+	{
+		let from = from.into();
+		let bounds = self.find_sector(&from, SlotSide::Output)?.bounds;
+
+		let name = (0..)
+			.map(|i| format!("delay_{}", i))
+			.find(|name| !self.schemes.contains_key(name))
+			.unwrap();
+
+		self.add_shapes_cube(&name, bounds, Timer::new(ticks), Rot::new(0, 0, 0))?;
+		self.connect(from, &name);
+		self.connect(&name, to);
+
+		Ok(name)
+	}
+
+	/// Adds single NOT gate (NOR gate, which is expected to have only
+	/// one input) with the given name. Just a shortcut for
+	/// `add(name, GateMode::NOR)`.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::testing::Simulator;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_not("not").unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.pass_input("_", "not", None as Option<String>).unwrap();
+	/// combiner.pass_output("_", "not", None as Option<String>).unwrap();
+	/// let (scheme, _) = combiner.compile().unwrap();
+	///
+	/// let mut sim = Simulator::new(&scheme);
+	/// sim.set_input("_", &[false]).unwrap();
+	/// sim.run(1);
+	/// assert_eq!(sim.output("_").unwrap(), vec![true]);
+	/// ```
+	pub fn add_not<N: Into<String>>(&mut self, name: N) -> Result<(), Error> {
+		self.add(name, GateMode::NOR)
+	}
+
+	/// Connects `from` output slot to `to` input slot through a cube of
+	/// NOT gates of the `from` slot size, so every point of `to` gets
+	/// inverted signal with one tick of delay. Just like
+	/// [`Combiner::connect_delayed`], gates scheme gets unique name
+	/// (`not_0`, `not_1`, etc.), which is returned, and becomes the last
+	/// added scheme to be placed with the positioner right after the call.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::testing::Simulator;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_shapes_cube("a", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add_shapes_cube("b", (4, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 2));
+	///
+	/// let nots = combiner.connect_inverted("a", "b").unwrap();
+	/// assert_eq!(nots, "not_0");
+	/// combiner.pos().place_last((0, 0, 1));
+	///
+	/// combiner.pass_input("_", "a", None as Option<String>).unwrap();
+	/// combiner.pass_output("_", "b", None as Option<String>).unwrap();
+	/// let (scheme, _) = combiner.compile().unwrap();
+	///
+	/// let mut sim = Simulator::new(&scheme);
+	/// sim.set_input_number("_", 0b1011).unwrap();
+	/// sim.run(3);
+	/// assert_eq!(sim.output_number("_").unwrap(), 0b0100);
+	/// ```
+	pub fn connect_inverted<P1, P2>(&mut self, from: P1, to: P2) -> Result<String, Error>
+		where P1: Into<String>,
+			  P2: Into<String>
+	{
+		let from = from.into();
+		let bounds = self.find_sector(&from, SlotSide::Output)?.bounds;
+
+		let name = (0..)
+			.map(|i| format!("not_{}", i))
+			.find(|name| !self.schemes.contains_key(name))
+			.unwrap();
+
+		self.add_shapes_cube(&name, bounds, GateMode::NOR, Rot::new(0, 0, 0))?;
+		self.connect(from, &name);
+		self.connect(&name, to);
+
+		Ok(name)
+	}
+
+	/// Splits `"scheme/slot/sector"` path into scheme name and
+	/// `"slot/sector"` path inside of that scheme (see
+	/// [`split_slot_path`]).
+	fn resolve_slot_path(&self, path: &str) -> (String, String) {
+		let (scheme_name, slot_name, sector_name) = split_slot_path(path, &self.default_slot);
+
+		match sector_name {
+			None => (scheme_name, slot_name),
+			Some(sector_name) => (scheme_name, format!("{}/{}", slot_name, sector_name)),
+		}
+	}
+
+	/// Finds sector of a slot of an added scheme by `"scheme/slot/sector"`
+	/// path.
+	fn find_sector(&self, path: &str, side: SlotSide) -> Result<&SlotSector, Error> {
+		let (scheme_name, slot_path) = self.resolve_slot_path(path);
+
+		let scheme = self.schemes.get(&scheme_name)
+			.ok_or(Error::NoSuchScheme { name: scheme_name })?;
+
+		let found = match side {
+			SlotSide::Input => scheme.input(slot_path),
+			SlotSide::Output => scheme.output(slot_path),
+		};
+
+		found.map(|(_, sector)| sector)
+			.ok_or(Error::NoSuchSlot { path: path.to_string() })
+	}
 }
 
 impl<P: Positioner> Combiner<P> {
@@ -973,12 +2047,77 @@ impl<P: Positioner> Combiner<P> {
 		self.bind_output(bind)
 	}
 
+	/// Just like `pass_input`, but the new slot has given size
+	/// (`new_bounds`) instead of the size of the target. Slots are
+	/// connected straight, so only the part, that fits into both
+	/// slots, gets connected.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::util::{Bounds, Point};
+	/// let mut combiner = Combiner::pos_manual();
+	/// // 16 bit internal bus
+	/// combiner.add_shapes_cube("bus", (16, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	///
+	/// // Only the low 8 bits are exposed
+	/// combiner.pass_input_resized("low", "bus", (8, 1, 1), None as Option<String>).unwrap();
+	///
+	/// let (scheme, _) = combiner.compile().unwrap();
+	/// let (slot, _) = scheme.input("low").unwrap();
+	/// assert_eq!(slot.bounds(), Bounds::new_ng(8, 1, 1));
+	/// assert_eq!(slot.get_point(Point::new_ng(7, 0, 0)).unwrap().len(), 1);
+	/// ```
+	pub fn pass_input_resized<S, Pt, B, K>(&mut self, name: S, path: Pt, new_bounds: B, new_kind: Option<K>) -> Result<(), Error>
+		where S: Into<String>,
+			  Pt: Into<String>,
+			  B: Into<Bounds>,
+			  K: Into<String>
+	{
+		let name = name.into();
+		let path = path.into();
+		let new_kind = new_kind.map(|k| k.into());
+
+		check_name_validity(&name)?;
+
+		let bind = self.parse_resized_pass_data(name, path, new_bounds.into(), new_kind, SlotSide::Input)?;
+		self.bind_input(bind)
+	}
+
+	/// Just like `pass_output`, but the new slot has given size
+	/// (`new_bounds`) instead of the size of the target. Slots are
+	/// connected straight, so only the part, that fits into both
+	/// slots, gets connected.
+	pub fn pass_output_resized<S, Pt, B, K>(&mut self, name: S, path: Pt, new_bounds: B, new_kind: Option<K>) -> Result<(), Error>
+		where S: Into<String>,
+			  Pt: Into<String>,
+			  B: Into<Bounds>,
+			  K: Into<String>
+	{
+		let name = name.into();
+		let path = path.into();
+		let new_kind = new_kind.map(|k| k.into());
+
+		check_name_validity(&name)?;
+
+		let bind = self.parse_resized_pass_data(name, path, new_bounds.into(), new_kind, SlotSide::Output)?;
+		self.bind_output(bind)
+	}
+
+	fn parse_resized_pass_data(&self, name: String, path: String, new_bounds: Bounds, new_kind: Option<String>, side: SlotSide) -> Result<Bind, Error> {
+		// Validates the target and gets the kind of the slot
+		let target_bind = self.parse_pass_data(name.clone(), path.clone(), new_kind, side)?;
+
+		let mut bind = Bind::new(name, target_bind.kind().clone(), new_bounds);
+		bind.connect_full(path);
+
+		Ok(bind)
+	}
+
 	fn parse_pass_data(&self, name: String, path: String, new_kind: Option<String>, side: SlotSide) -> Result<Bind, Error> {
-		let (scheme_name, slot_name) = split_first_token(path.clone());
-		let slot_name = match slot_name {
-			None => "".to_string(),
-			Some(name) => name,
-		};
+		let (scheme_name, slot_name) = self.resolve_slot_path(&path);
 
 		let scheme = match self.schemes.get(&scheme_name) {
 			None => return Err(Error::PassHasInvalidTarget {
@@ -1002,9 +2141,15 @@ impl<P: Positioner> Combiner<P> {
 			None => return Err(Error::PassHasInvalidTarget {
 				pass_name: name,
 				pass_side: side,
-				tip: match &self.debug_name {
-					None => format!("Slot {}/{} was not found (Scheme exists, but not the slot).", scheme_name, slot_name),
-					Some(name) => format!("Slot {}/{} was not found in '{}' (Scheme exists, but not the slot).", scheme_name, slot_name, name),
+				tip: {
+					let msg = match &self.debug_name {
+						None => format!("Slot {}/{} was not found (Scheme exists, but not the slot).", scheme_name, slot_name),
+						Some(name) => format!("Slot {}/{} was not found in '{}' (Scheme exists, but not the slot).", scheme_name, slot_name, name),
+					};
+					match scheme.origin() {
+						None => msg,
+						Some(origin) => format!("{} Scheme '{}' was built by '{}'.", msg, scheme_name, origin),
+					}
 				},
 			}),
 
@@ -1100,9 +2245,24 @@ impl<P: Positioner> Combiner<P> {
 	pub fn compile(self) -> Result<(Scheme, InvalidActs), CompileError<<P as Positioner>::Error>>
 	{
 		// Placing schemes
-		let schemes = self.positioner.arrange(self.schemes)
+		let mut schemes = self.schemes;
+		let schemes: Vec<(String, Scheme)> = self.schemes_order.into_iter()
+			.map(|name| {
+				let scheme = schemes.remove(&name).unwrap();
+				(name, scheme)
+			})
+			.collect();
+
+		let schemes = self.positioner.arrange(schemes)
 			.map_err(|error| CompileError::PositionerError(error))?;
 
+		if self.check_collisions {
+			let pairs = find_overlaps(&schemes);
+			if !pairs.is_empty() {
+				return Err(CompileError::SchemesOverlap { pairs });
+			}
+		}
+
 		let mut invalid_acts = InvalidActs::new();
 		let mut inputs_map: HashMap<String, (usize, Vec<Slot>)> = HashMap::new();
 		let mut outputs_map: HashMap<String, (usize, Vec<Slot>)> = HashMap::new();
@@ -1120,7 +2280,7 @@ impl<P: Positioner> Combiner<P> {
 
 		// Compiling input binds
 		let inputs: Vec<Slot> = self.inputs.into_iter()
-			.map(|bind| bind.compile(&inputs_map))
+			.map(|bind| bind.compile_with_default(&inputs_map, &self.default_slot))
 			.map(|(slot, invalid)| {
 				let invalid = invalid.into_iter()
 					.map(|x| (slot.name().clone(), x));
@@ -1132,7 +2292,7 @@ impl<P: Positioner> Combiner<P> {
 
 		// Compiling output binds
 		let outputs: Vec<Slot> = self.outputs.into_iter()
-			.map(|bind| bind.compile(&outputs_map))
+			.map(|bind| bind.compile_with_default(&outputs_map, &self.default_slot))
 			.map(|(slot, invalid)| {
 				let invalid = invalid.into_iter()
 					.map(|x| (slot.name().clone(), x));
@@ -1144,8 +2304,8 @@ impl<P: Positioner> Combiner<P> {
 
 		// Compiling all the connections
 		for conn in self.connections {
-			let slot_from = get_scheme_slot(&conn.from, &outputs_map);
-			let slot_to = get_scheme_slot(&conn.to, &inputs_map);
+			let slot_from = get_scheme_slot(&conn.from, &outputs_map, &self.default_slot);
+			let slot_to = get_scheme_slot(&conn.to, &inputs_map, &self.default_slot);
 
 			if slot_from.is_none() || slot_to.is_none() {
 				invalid_acts.connections.push(conn);
@@ -1154,13 +2314,33 @@ impl<P: Positioner> Combiner<P> {
 			let slot_from = slot_from.unwrap();
 			let slot_to = slot_to.unwrap();
 
-			compile_connection(slot_from, slot_to, conn.connection, &mut shapes);
+			// Connection is only cloned, if it has to be chained with adaptor
+			let mut adapted = None;
+			let (from_kind, to_kind) = (&slot_from.2.kind, &slot_to.2.kind);
+			if !kinds_compatible(from_kind, to_kind) {
+				match self.adaptors.get(&(from_kind.clone(), to_kind.clone())) {
+					Some(adaptor) => adapted = Some(adaptor.clone().chain(Some(slot_to.2.bounds), conn.connection.clone())),
+					None => if self.strict_kinds {
+						invalid_acts.kind_mismatches.push((conn.clone(), from_kind.clone(), to_kind.clone()));
+					},
+				}
+			}
+
+			let connection = adapted.as_deref().unwrap_or(conn.connection.as_ref());
+			let pairs = compile_connection(slot_from, slot_to, connection, &mut shapes);
+			if self.strict_connections && pairs == 0 {
+				invalid_acts.connections.push(conn);
+			}
+		}
+
+		if self.auto_split_overflow {
+			split_overflow(&mut shapes, self.conns_limit);
 		}
 
 		if !self.conns_overflow_allowed {
 			// Check if some shape contains more than 255 connections
 			let ovf_shapes: Vec<bool> = shapes.iter()
-				.map(|(_, _, shape)| shape.connections().len() > (MAX_CONNECTIONS as usize))
+				.map(|(_, _, shape)| shape.connections().len() > (self.conns_limit as usize))
 				.collect();
 
 			for (i, is_ovf) in ovf_shapes.iter().enumerate() {
@@ -1200,7 +2380,7 @@ impl<P: Positioner> Combiner<P> {
 							more than {} connections connected to it's input or output it is called \
 							connections overflow. This is bad, because it is too buggy and, probably, \
 							it is not the thing you want. If you want to intentionally allow connections \
-							overflow, use `allow_conns_overflow` method before compilation.", MAX_CONNECTIONS);
+							overflow, use `allow_conns_overflow` method before compilation.", self.conns_limit);
 						match &self.debug_name {
 							None => msg,
 							Some(name) => format!("Combiner '{}' compilation: {}", name, msg),
@@ -1210,16 +2390,131 @@ impl<P: Positioner> Combiner<P> {
 			}
 		}
 
-		let scheme = Scheme::create(shapes, inputs, outputs);
+		let mut scheme = Scheme::create(shapes, inputs, outputs);
+		if let Some(name) = self.debug_name {
+			scheme.set_origin(name);
+		}
+
+		Ok((scheme, invalid_acts))
+	}
+
+	/// Same as [`Combiner::compile`], but also applies common
+	/// post-processing to the compiled scheme, as set by `opts`. See
+	/// [`OptimizeOpts`] for the order of the steps.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::combiner::{Combiner, OptimizeOpts};
+	/// # use crate::sm_logic::shape::vanilla::GateMode;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_iter([("input", GateMode::OR), ("output", GateMode::OR), ("unused", GateMode::AND)]).unwrap();
+	/// combiner.connect("input", "output");
+	/// combiner.pos().place_iter([
+	/// 	("input", (0, 0, 0)),
+	/// 	("output", (0, 1, 0)),
+	/// 	("unused", (0, 2, 0)),
+	/// ]);
+	/// combiner.pass_input("_", "input", None as Option<String>).unwrap();
+	/// combiner.pass_output("_", "output", None as Option<String>).unwrap();
+	///
+	/// let (scheme, _invalid) = combiner.compile_optimized(OptimizeOpts::default()).unwrap();
+	/// assert_eq!(scheme.shapes_count(), 2);
+	/// ```
+	pub fn compile_optimized(self, opts: OptimizeOpts) -> Result<(Scheme, InvalidActs), CompileError<<P as Positioner>::Error>>
+	{
+		let (mut scheme, invalid_acts) = self.compile()?;
+
+		if opts.dedup_gates {
+			scheme.dedup_gates();
+		}
+
+		if opts.remove_unused {
+			scheme.remove_unused();
+		}
+
+		if let Some(block) = opts.replace_unused_with {
+			scheme.replace_unused_with(block);
+		}
+
 		Ok((scheme, invalid_acts))
 	}
+
+	/// Estimates size of the compiled scheme without compiling it.
+	/// Shapes count is just the sum of shapes counts of all the added
+	/// schemes (gates added by helpers like `connect_safe` or
+	/// `connect_delayed` are already added schemes). Gates, that
+	/// compilation may add to split overflowing connections, are not
+	/// counted.
+	///
+	/// Bounds are calculated by arranging bounding boxes of the schemes
+	/// with a copy of the positioner, so the combiner stays untouched.
+	/// If the positioner fails to arrange the schemes, bounds are `None`.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::util::Bounds;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_shapes_cube("a", (4, 2, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.add("b", GateMode::AND).unwrap();
+	///
+	/// let estimate = combiner.estimate();
+	/// assert_eq!(estimate.shapes_count, 9);
+	/// // "b" is not placed yet
+	/// assert!(estimate.bounds.is_none());
+	///
+	/// combiner.pos().place_last((0, 0, 5));
+	/// assert_eq!(combiner.estimate().bounds, Some(Bounds::new_ng(4, 2, 6)));
+	/// ```
+	pub fn estimate(&self) -> Estimate {
+		let shapes_count = self.schemes.values()
+			.map(|scheme| scheme.shapes_count())
+			.sum();
+
+		// Schemes are replaced with single blocks of the same bounds
+		let boxes: Vec<(String, Scheme)> = self.schemes_order.iter()
+			.map(|name| {
+				let (start, size) = self.schemes[name].calculate_bounds();
+				let stand_in = if size.volume() == 0 {
+					Scheme::empty()
+				} else {
+					Scheme::create(
+						vec![(start, Rot::new(0, 0, 0), BlockBody::new(BlockType::Glass, size))],
+						vec![],
+						vec![]
+					)
+				};
+
+				(name.clone(), stand_in)
+			})
+			.collect();
+
+		let bounds = self.positioner.clone().arrange(boxes)
+			.ok()
+			.map(|arranged| {
+				let shapes = arranged.into_values()
+					.flat_map(|(pos, rot, scheme)| scheme.disassemble(0, pos, rot).0)
+					.collect();
+
+				Scheme::create(shapes, vec![], vec![]).bounds()
+			});
+
+		Estimate {
+			shapes_count,
+			bounds,
+		}
+	}
 }
 
 fn compile_connection(from: (usize, &Slot, &SlotSector),
 					  to: (usize, &Slot, &SlotSector),
-					  with: Box<dyn Connection>,
-					  shapes: &mut Vec<(Point, Rot, Shape)>)
+					  with: &dyn Connection,
+					  shapes: &mut Vec<(Point, Rot, Shape)>) -> usize
 {
+	let mut pairs_count = 0;
+
 	let p2p_conns = with.connect(from.2.bounds, to.2.bounds);
 	let from_offset = from.2.pos;
 	let to_offset = to.2.pos;
@@ -1233,6 +2528,7 @@ fn compile_connection(from: (usize, &Slot, &SlotSector),
 			continue;
 		}
 
+		pairs_count += 1;
 		let from_shapes = from.1.get_point(from_offset + start).unwrap();
 		let from_start_shape = from.0;
 
@@ -1247,26 +2543,150 @@ fn compile_connection(from: (usize, &Slot, &SlotSector),
 			);
 		}
 	}
+
+	pairs_count
 }
 
-fn get_scheme_slot<'a>(path: &String, slots: &'a HashMap<String, (usize, Vec<Slot>)>) -> Option<(usize, &'a Slot, &'a SlotSector)> {
-	let (scheme_name, slot_name) = split_first_token(path.clone());
-	let slot_name = match slot_name {
-		None => "".to_string(),		// Default slot name
-		Some(name) => name,
-	};
+/// Inserts buffer gates until no shape has more than `limit` outgoing
+/// or incoming connections. New shapes are appended to the end, so ids
+/// of existing shapes do not change.
+fn split_overflow(shapes: &mut Vec<(Point, Rot, Shape)>, limit: u32) {
+	let max = limit as usize;
+
+	// Fan-out: shape is connected to too many shapes. All of its targets
+	// are moved to OR buffers, so every target gets the same delay.
+	let mut id = 0;
+	while id < shapes.len() {
+		if shapes[id].2.connections().len() > max {
+			let (pos, rot, _) = shapes[id].clone();
+			let targets = std::mem::take(shapes[id].2.connections_mut());
+
+			for chunk in targets.chunks(max) {
+				let mut buffer = Gate::new(GateMode::OR);
+				buffer.extend_conn(chunk.iter().cloned());
+
+				let buffer_id = shapes.len();
+				shapes[id].2.push_conn(buffer_id);
+				shapes.push((pos, rot.clone(), buffer));
+			}
+
+			// Source may still have too many buffers, so check it again
+			continue;
+		}
+		id += 1;
+	}
+
+	// Fan-in: too many shapes are connected to the shape. Sources are
+	// grouped with buffers of the mode, that keeps gate logic the same.
+	let mut sources: Vec<Vec<usize>> = vec![vec![]; shapes.len()];
+	for (source, (_, _, shape)) in shapes.iter().enumerate() {
+		for target in shape.connections() {
+			sources[*target].push(source);
+		}
+	}
+
+	let mut id = 0;
+	while id < shapes.len() {
+		if sources[id].len() > max {
+			let (pos, rot, target) = shapes[id].clone();
+			let mode = fan_in_buffer_mode(&target);
+			let target_sources = std::mem::take(&mut sources[id]);
+
+			for chunk in target_sources.chunks(max) {
+				let buffer_id = shapes.len();
+				let mut buffer = Gate::new(mode);
+				buffer.push_conn(id);
+
+				for source in chunk {
+					let conns = shapes[*source].2.connections_mut();
+					if let Some(conn) = conns.iter_mut().find(|conn| **conn == id) {
+						*conn = buffer_id;
+					}
+				}
+
+				shapes.push((pos, rot.clone(), buffer));
+				sources.push(chunk.to_vec());
+				sources[id].push(buffer_id);
+			}
+
+			// Target may still have too many buffers, so check it again
+			continue;
+		}
+		id += 1;
+	}
+}
+
+/// Returns pairs of names of the schemes, which bounding boxes intersect.
+/// Names in each pair and pairs themselves are sorted.
+fn find_overlaps(schemes: &HashMap<String, (Point, Rot, Scheme)>) -> Vec<(String, String)> {
+	let boxes: Vec<(&String, Point, Point)> = schemes.iter()
+		.filter(|(_, (_, _, scheme))| scheme.shapes_count() > 0)
+		.map(|(name, (pos, rot, scheme))| {
+			// Shapes positioned exactly as they are after compilation
+			let (shapes, _, _) = scheme.clone().disassemble(0, *pos, rot.clone());
+			let (start, bounds) = Scheme::create(shapes, vec![], vec![]).calculate_bounds();
+
+			(name, start, start + bounds.cast::<i32>())
+		})
+		.collect();
+
+	let mut pairs = vec![];
+	for (i, (name_a, start_a, end_a)) in boxes.iter().enumerate() {
+		for (name_b, start_b, end_b) in &boxes[(i + 1)..] {
+			let intersect = (0..3).all(|axis|
+				start_a[axis] < end_b[axis] && start_b[axis] < end_a[axis]
+			);
+
+			if intersect {
+				let mut pair = ((*name_a).clone(), (*name_b).clone());
+				if pair.0 > pair.1 {
+					pair = (pair.1, pair.0);
+				}
+				pairs.push(pair);
+			}
+		}
+	}
 
-	let (slot_name, slot_sector_name) = split_first_token(slot_name);
-	let slot_sector_name = match slot_sector_name {
-		None => "".to_string(),		// Default sector name
-		Some(sector) => sector,
+	pairs.sort();
+	pairs
+}
+
+/// Returns the mode of buffer gates, that can combine inputs of the
+/// shape without changing its logic: AND-like gates need AND buffers,
+/// XOR-like - XOR buffers, everything else - OR buffers.
+fn fan_in_buffer_mode(shape: &Shape) -> GateMode {
+	match shape.gate_mode() {
+		Some(GateMode::AND) | Some(GateMode::NAND) => GateMode::AND,
+		Some(GateMode::XOR) | Some(GateMode::XNOR) => GateMode::XOR,
+		_ => GateMode::OR,
+	}
+}
+
+/// Splits `"scheme/slot/sector"` path into its parts. Empty slot name is
+/// replaced with `default_slot`. All the paths of [`Combiner`] are parsed
+/// with this function.
+fn split_slot_path(path: &str, default_slot: &str) -> (String, String, Option<String>) {
+	let (scheme_name, slot_name) = split_first_token(path.to_string());
+	let (slot_name, sector_name) = split_first_token(slot_name.unwrap_or_default());
+
+	let slot_name = if slot_name.is_empty() {
+		default_slot.to_string()
+	} else {
+		slot_name
 	};
 
+	(scheme_name, slot_name, sector_name)
+}
+
+fn get_scheme_slot<'a>(path: &String, slots: &'a HashMap<String, (usize, Vec<Slot>)>, default_slot: &str) -> Option<(usize, &'a Slot, &'a SlotSector)> {
+	let (scheme_name, slot_name, slot_sector_name) = split_slot_path(path, default_slot);
+	let slot_sector_name = slot_sector_name.unwrap_or_default();	// Default sector name
+
 	match slots.get(&scheme_name) {
 		None => None,
 
 		Some((start_shape, all_scheme_slots)) => {
-			match scheme::find_slot(slot_name.clone(), all_scheme_slots) {
+			match scheme::find_slot_or_default(slot_name, all_scheme_slots, default_slot) {
 				None => None,
 				Some(slot) => slot.get_sector(&slot_sector_name)
 					.map(|sector| (*start_shape, slot, sector))
@@ -1275,3 +2695,159 @@ fn get_scheme_slot<'a>(path: &String, slots: &'a HashMap<String, (usize, Vec<Slo
 		}
 	}
 }
+
+#[test]
+fn connect_sector_test() {
+	use crate::presets::make_rational_bind;
+	use crate::testing::Simulator;
+
+	// 4 integer bits and 2 fractional bits
+	let mut a = Combiner::pos_manual();
+	a.add_shapes_cube("bits", (6, 1, 1), GateMode::OR, Rot::new(0, 0, 0)).unwrap();
+	a.pos().place_last((0, 0, 0));
+	a.pass_input("_", "bits", None as Option<String>).unwrap();
+	a.bind_output(make_rational_bind("rational", "bits", 4, 2, 2, 0)).unwrap();
+	let (a, _) = a.compile().unwrap();
+
+	// 6 integer bits and 2 fractional bits
+	let mut b = Combiner::pos_manual();
+	b.add_shapes_cube("bits", (8, 1, 1), GateMode::OR, Rot::new(0, 0, 0)).unwrap();
+	b.pos().place_last((0, 0, 0));
+	b.pass_output("_", "bits", None as Option<String>).unwrap();
+	b.bind_input(make_rational_bind("rational", "bits", 6, 2, 2, 0)).unwrap();
+	let (b, _) = b.compile().unwrap();
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.add("a", a).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+	combiner.add("b", b).unwrap();
+	combiner.pos().place_last((0, 1, 0));
+
+	combiner.connect_sector("a/rational/integer", "b/rational/integer").unwrap();
+	assert!(matches!(
+		combiner.connect_sector("a/rational/integer", "b/rational/nothing"),
+		Err(Error::NoSuchSlot { .. })
+	));
+	assert!(matches!(
+		combiner.connect_sector("c/rational/integer", "b/rational/integer"),
+		Err(Error::NoSuchScheme { .. })
+	));
+
+	combiner.pass_input("_", "a", None as Option<String>).unwrap();
+	combiner.pass_output("_", "b", None as Option<String>).unwrap();
+	let (scheme, invalid_acts) = combiner.compile().unwrap();
+	assert_eq!(invalid_acts.connections.len(), 0);
+
+	// Integer part is 0b1011, fractional part is not connected
+	let mut sim = Simulator::new(&scheme);
+	sim.set_input_number("_", 0b101111).unwrap();
+	sim.run(2);
+	assert_eq!(sim.output_number("_").unwrap(), 0b101100);
+}
+
+#[test]
+fn default_slot_name_test() {
+	use crate::testing::Simulator;
+
+	let mut inner = Combiner::pos_manual();
+	inner.add("gate", GateMode::OR).unwrap();
+	inner.pos().place_last((0, 0, 0));
+	inner.pass_input("in", "gate", None as Option<String>).unwrap();
+	inner.pass_output("out", "gate", None as Option<String>).unwrap();
+	let (inner, _) = inner.compile().unwrap();
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_default_slot_name("out");
+	combiner.add("first", inner.clone()).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+	combiner.add("second", inner).unwrap();
+	combiner.pos().place_last((1, 0, 0));
+
+	// "first" is resolved to "first/out"
+	combiner.connect("first", "second/in");
+	assert_eq!(combiner.connections_touching("first/out"), vec![0]);
+
+	combiner.pass_input("_", "first/in", None as Option<String>).unwrap();
+	combiner.pass_output("_", "second", None as Option<String>).unwrap();
+	let (scheme, invalid) = combiner.compile().unwrap();
+	assert!(invalid.connections.is_empty());
+	assert!(invalid.out_bind_conns.is_empty());
+
+	let mut sim = Simulator::new(&scheme);
+	sim.set_input("_", &[true]).unwrap();
+	sim.run(2);
+	assert_eq!(sim.output("_").unwrap(), vec![true]);
+}
+
+#[test]
+fn strict_connections_test() {
+	use crate::presets::shift_connection;
+
+	let build = |strict: bool| {
+		let mut combiner = Combiner::pos_manual();
+		combiner.set_strict_connections(strict);
+		combiner.add_shapes_cube("a", (4, 1, 1), GateMode::OR, Rot::new(0, 0, 0)).unwrap();
+		combiner.pos().place_last((0, 0, 0));
+		combiner.add_shapes_cube("b", (4, 1, 1), GateMode::OR, Rot::new(0, 0, 0)).unwrap();
+		combiner.pos().place_last((0, 0, 1));
+
+		combiner.connect("a", "b");
+		// All the points are shifted out of "b" bounds
+		combiner.custom("a", "b", shift_connection((10, 0, 0)));
+
+		let (_, invalid) = combiner.compile().unwrap();
+		invalid.connections
+	};
+
+	assert_eq!(build(false).len(), 0);
+
+	let invalid = build(true);
+	assert_eq!(invalid.len(), 1);
+	assert_eq!(invalid[0].from, "a");
+	assert_eq!(invalid[0].to, "b");
+}
+
+#[test]
+fn register_adaptor_test() {
+	use crate::testing::Simulator;
+
+	let kinded_cube = |kind: &str| {
+		let mut inner = Combiner::pos_manual();
+		inner.add_shapes_cube("gates", (4, 1, 1), GateMode::OR, Rot::new(0, 0, 0)).unwrap();
+		inner.pos().place_last((0, 0, 0));
+		inner.pass_input("_", "gates", Some(kind)).unwrap();
+		inner.pass_output("_", "gates", Some(kind)).unwrap();
+		inner.compile().unwrap().0
+	};
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.strict_kinds();
+	combiner.add("source", kinded_cube("reversed_binary")).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+	combiner.add("target", kinded_cube("binary")).unwrap();
+	combiner.pos().place_last((0, 0, 1));
+	combiner.connect("source", "target");
+	combiner.pass_input("_", "source", None as Option<String>).unwrap();
+	combiner.pass_output("_", "target", None as Option<String>).unwrap();
+	combiner.register_adaptor("reversed_binary", "binary", ConnMap::new(|(point, in_bounds), _| {
+		Some(Point::new(*in_bounds.x() as i32 - *point.x() - 1, 0, 0))
+	}));
+
+	let (scheme, invalid) = combiner.clone().compile().unwrap();
+	assert!(invalid.kind_mismatches.is_empty());
+
+	let mut sim = Simulator::new(&scheme);
+	sim.set_input("_", &[true, true, false, false]).unwrap();
+	sim.run(3);
+	assert_eq!(sim.output("_").unwrap(), vec![false, false, true, true]);
+
+	// Custom connection is applied after the adaptor, straight one
+	// is still there
+	combiner.map("source", "target", |(point, _), _| Some(point + Point::new(1, 0, 0)));
+	let (scheme, _) = combiner.compile().unwrap();
+
+	let mut sim = Simulator::new(&scheme);
+	sim.set_input("_", &[false, true, false, false]).unwrap();
+	sim.run(3);
+	assert_eq!(sim.output("_").unwrap(), vec![false, false, true, true]);
+}
@@ -15,6 +15,28 @@ pub trait Connection: DynClone + Debug {
 	fn connect(&self, start: Bounds, end: Bounds) -> Vec<(Point, Point)>;
 
 	fn chain(self: Box<Self>, virtual_slot: Option<Bounds>, other: Box<dyn Connection>) -> Box<dyn Connection>;
+
+	/// Returns all the point-to-point pairs, that the connection makes
+	/// between slots of given bounds, sorted. Useful for debugging of
+	/// custom connections.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::connection::{Connection, ConnMap};
+	/// # use sm_logic::util::{Bounds, Point};
+	/// let connection = ConnMap::new(|(point, _), _| Some(point * 2));
+	/// let preview = connection.debug_preview(Bounds::new_ng(2, 1, 1), Bounds::new_ng(4, 1, 1));
+	///
+	/// assert_eq!(preview, vec![
+	/// 	(Point::new_ng(0, 0, 0), Point::new_ng(0, 0, 0)),
+	/// 	(Point::new_ng(1, 0, 0), Point::new_ng(2, 0, 0)),
+	/// ]);
+	/// ```
+	fn debug_preview(&self, start: Bounds, end: Bounds) -> Vec<(Point, Point)> {
+		let mut pairs = self.connect(start, end);
+		pairs.sort_by(compare_two_vec_pairs);
+		pairs
+	}
 }
 
 dyn_clone::clone_trait_object!(Connection);
@@ -100,6 +122,32 @@ impl ConnJoint {
 			}
 		)
 	}
+
+	/// Returns count of chained connections.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::connection::{ConnJoint, ConnStraight};
+	/// let joint = ConnJoint::new(ConnStraight::new());
+	///
+	/// assert_eq!(joint.len(), 1);
+	/// assert_eq!(joint.stages()[0].0, None);
+	/// ```
+	pub fn len(&self) -> usize {
+		self.connections.len()
+	}
+
+	/// Always `false`: joint has at least the connection it was created
+	/// with.
+	pub fn is_empty(&self) -> bool {
+		self.connections.is_empty()
+	}
+
+	/// Returns chained connections with bounds of virtual slots before
+	/// them (see [`Connection::chain`]).
+	pub fn stages(&self) -> &Vec<(Option<Bounds>, Box<dyn Connection>)> {
+		&self.connections
+	}
 }
 
 impl Connection for ConnJoint {
@@ -312,6 +360,117 @@ impl Connection for ConnDim {
 }
 
 
+/// [`ConnDim`] with each end point shifted by given vector. End points,
+/// that are shifted out of the end `Slot` bounds, are dropped.
+///
+/// Is the same as [`ConnDim`] chained with a shifting [`ConnMap`], but
+/// does not need a [`ConnJoint`].
+///
+/// # Example
+/// ```
+/// # use sm_logic::connection::Connection;
+/// # use sm_logic::connection::ConnDimShift;
+/// # use sm_logic::util::{Bounds, Point};
+/// // Single control point fans out to the bits 3..8 of the 8 bit target
+/// let conn = ConnDimShift::new((true, false, false), (3, 0, 0));
+/// let vectors = conn.connect(Bounds::new_ng(1, 1, 1), Bounds::new_ng(8, 1, 1));
+///
+/// assert_eq!(vectors.len(), 5);
+/// assert!(vectors.iter().all(|(start, _)| *start == Point::new(0, 0, 0)));
+/// assert_eq!(vectors[0].1, Point::new(3, 0, 0));
+/// assert_eq!(vectors[4].1, Point::new(7, 0, 0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnDimShift {
+	dim: ConnDim,
+	shift: Point,
+}
+
+impl ConnDimShift {
+	pub fn new<P: Into<Point>>(adapt_axes: (bool, bool, bool), shift: P) -> Box<ConnDimShift> {
+		Box::new(
+			ConnDimShift {
+				dim: *ConnDim::new(adapt_axes),
+				shift: shift.into(),
+			}
+		)
+	}
+}
+
+impl Connection for ConnDimShift {
+	fn connect(&self, start: Bounds, end: Bounds) -> Vec<(Point, Point)> {
+		self.dim.connect(start, end)
+			.into_iter()
+			.map(|(start, end)| (start, end + self.shift))
+			.filter(|(_, end_point)| is_point_in_bounds(*end_point, end))
+			.collect()
+	}
+
+	fn chain(self: Box<Self>, virtual_slot: Option<Bounds>, other: Box<dyn Connection>) -> Box<dyn Connection> {
+		ConnJoint::new(self).chain(virtual_slot, other)
+	}
+}
+
+/// Same as [`ConnStraight`], but end points are shifted by `shift`
+/// and wrapped around modulo the smaller slot size on each axis. Makes
+/// rotations of buses and rings of cells.
+///
+/// # Example
+/// ```
+/// # use sm_logic::connection::Connection;
+/// # use sm_logic::connection::ConnCyclic;
+/// # use sm_logic::util::{Bounds, Point};
+/// let conn = ConnCyclic::new((1, 0, 0));
+/// let slot_size = Bounds::new_ng(8, 1, 1);
+/// let vectors = conn.connect(slot_size, slot_size);
+///
+/// assert_eq!(vectors.len(), 8);
+/// assert_eq!(vectors[0], (Point::new(0, 0, 0), Point::new(1, 0, 0)));
+/// // Point 7 wraps to point 0
+/// assert_eq!(vectors[7], (Point::new(7, 0, 0), Point::new(0, 0, 0)));
+///
+/// // Negative shift wraps the other way
+/// let vectors = ConnCyclic::new((-1, 0, 0)).connect(slot_size, slot_size);
+/// assert_eq!(vectors[0], (Point::new(0, 0, 0), Point::new(7, 0, 0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnCyclic {
+	shift: Point,
+}
+
+impl ConnCyclic {
+	pub fn new<P: Into<Point>>(shift: P) -> Box<ConnCyclic> {
+		Box::new(
+			ConnCyclic {
+				shift: shift.into(),
+			}
+		)
+	}
+}
+
+impl Connection for ConnCyclic {
+	fn connect(&self, start: Bounds, end: Bounds) -> Vec<(Point, Point)> {
+		let size = start.component_min(end).cast::<i32>();
+
+		ConnStraight::new().connect(start, end)
+			.into_iter()
+			.map(|(point, _)| {
+				let shifted = point + self.shift;
+				let wrapped = Point::new(
+					shifted.x().rem_euclid(*size.x()),
+					shifted.y().rem_euclid(*size.y()),
+					shifted.z().rem_euclid(*size.z()),
+				);
+				(point, wrapped)
+			})
+			.collect()
+	}
+
+	fn chain(self: Box<Self>, virtual_slot: Option<Bounds>, other: Box<dyn Connection>) -> Box<dyn Connection> {
+		ConnJoint::new(self).chain(virtual_slot, other)
+	}
+}
+
 /// Filters point-to-point connections of other `Connection`.
 ///
 /// # Example
@@ -439,4 +598,79 @@ impl Debug for ConnMap {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		write!(f, "ConnMap {{?}}")
 	}
-}
\ No newline at end of file
+}
+
+/// Just like [`ConnMap`], but maps each point of start `Slot` to any
+/// amount of points of end `Slot`. End points, that are out of the end
+/// `Slot` bounds, are dropped.
+///
+/// # Example
+/// ```
+/// # use sm_logic::connection::Connection;
+/// # use sm_logic::connection::ConnMapMulti;
+/// # use sm_logic::util::{Bounds, Point};
+/// // Each point is connected to itself and to the point 4 X further
+/// let conn = ConnMapMulti::new(|(point, _), _| vec![point, point + Point::new(4, 0, 0)]);
+/// let vectors = conn.connect(Bounds::new_ng(2, 1, 1), Bounds::new_ng(5, 1, 1));
+///
+/// assert_eq!(vectors, vec![
+/// 	(Point::new(0, 0, 0), Point::new(0, 0, 0)),
+/// 	(Point::new(0, 0, 0), Point::new(4, 0, 0)),
+/// 	(Point::new(1, 0, 0), Point::new(1, 0, 0)),
+/// ]);
+/// ```
+#[derive(Clone)]
+pub struct ConnMapMulti {
+	function: Arc<dyn Fn((Point, Bounds), Bounds) -> Vec<Point>>,
+}
+
+impl ConnMapMulti {
+	/// Argument is: Fn((start point, start bounds), end bounds) -> Vec<end point>
+	pub fn new<F>(function: F) -> Box<ConnMapMulti>
+		where F: Fn((Point, Bounds), Bounds) -> Vec<Point> + 'static
+	{
+		Box::new(
+			ConnMapMulti {
+				function: Arc::new(function)
+			}
+		)
+	}
+
+	/// Argument is: Fn((start point, start bounds), end bounds) -> Vec<end point>
+	pub fn from_arc(function: Arc<dyn Fn((Point, Bounds), Bounds) -> Vec<Point>>) -> Box<ConnMapMulti>
+	{
+		Box::new( ConnMapMulti { function } )
+	}
+}
+
+impl Connection for ConnMapMulti {
+	fn connect(&self, start: Bounds, end: Bounds) -> Vec<(Point, Point)> {
+		let mut vectors: Vec<(Point, Point)> = Vec::new();
+
+		for x in 0..(*start.x() as i32) {
+			for y in 0..(*start.y() as i32) {
+				for z in 0..(*start.z() as i32) {
+					let start_point = Point::new(x, y, z);
+
+					for end_point in (*self.function)((start_point, start), end) {
+						if is_point_in_bounds(end_point, end) {
+							vectors.push((start_point, end_point));
+						}
+					}
+				}
+			}
+		}
+
+		vectors
+	}
+
+	fn chain(self: Box<Self>, virtual_slot: Option<Bounds>, other: Box<dyn Connection>) -> Box<dyn Connection> {
+		ConnJoint::new(self).chain(virtual_slot, other)
+	}
+}
+
+impl Debug for ConnMapMulti {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "ConnMapMulti {{?}}")
+	}
+}
@@ -7,4 +7,6 @@ pub mod shape;
 pub mod positioner;
 pub mod bind;
 pub mod presets;
-pub mod bp_manager;
\ No newline at end of file
+pub mod bp_manager;
+pub mod prelude;
+pub mod testing;
\ No newline at end of file
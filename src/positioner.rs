@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use crate::positioner::ManualPosError::{SchemeHasNoPosition, SchemeIsNotPlaced};
 use crate::scheme::Scheme;
-use crate::util::{Point, Rot};
+use crate::util::{Facing, Orient, Point, Rot};
 
 /// `Positioner` is an object, that gives each `Combiner`'s scheme a
 /// position.
@@ -19,9 +19,59 @@ pub trait Positioner: Debug + Clone {
 	/// added `Scheme` is passed.
 	fn set_last_scheme(&mut self, scheme_name: String);
 
-	/// Converts HashMap<String, Scheme> to HashMap<String, (Point, Rot, Scheme)> -
+	/// This function is called by `Combiner`, when the scheme `old` is
+	/// renamed to `new`. Positioners, that store any data by scheme
+	/// names, should move it to the new name.
+	fn rename_scheme(&mut self, _old: &str, _new: &str) {}
+
+	/// This function is called by `Combiner` methods, that add schemes
+	/// at known positions (like `Combiner::add_grid`). Positioners,
+	/// that do not place schemes by hand, can ignore it.
+	fn suggest_pos(&mut self, _scheme_name: &str, _pos: Point) {}
+
+	/// Converts Vec<(String, Scheme)> to HashMap<String, (Point, Rot, Scheme)> -
 	/// assigns physical positions and rotations to each of the schemes.
-	fn arrange(self, schemes: HashMap<String, Scheme>) -> Result<HashMap<String, (Point, Rot, Scheme)>, Self::Error>;
+	/// Schemes are given in the order they were added to the `Combiner`.
+	///
+	/// # Example
+	/// ```
+	/// # use std::collections::HashMap;
+	/// # use sm_logic::combiner::Combiner;
+	/// # use sm_logic::positioner::Positioner;
+	/// # use sm_logic::scheme::Scheme;
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use sm_logic::util::{Point, Rot};
+	/// // Places schemes in a row along X axis in order of addition
+	/// #[derive(Debug, Clone)]
+	/// struct RowPos;
+	///
+	/// impl Positioner for RowPos {
+	/// 	type Error = ();
+	///
+	/// 	fn set_last_scheme(&mut self, _scheme_name: String) {}
+	///
+	/// 	fn arrange(self, schemes: Vec<(String, Scheme)>) -> Result<HashMap<String, (Point, Rot, Scheme)>, ()> {
+	/// 		let mut x = 0;
+	/// 		let mut posed = HashMap::new();
+	///
+	/// 		for (name, scheme) in schemes {
+	/// 			let width = *scheme.bounds().x() as i32;
+	/// 			posed.insert(name, (Point::new_ng(x, 0, 0), Rot::new(0, 0, 0), scheme));
+	/// 			x += width;
+	/// 		}
+	///
+	/// 		Ok(posed)
+	/// 	}
+	/// }
+	///
+	/// let mut combiner = Combiner::new(RowPos);
+	/// combiner.add_shapes_cube("wide", (3, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.add("gate", GateMode::AND).unwrap();
+	///
+	/// let (scheme, _) = combiner.compile().unwrap();
+	/// assert_eq!(scheme.bounds().tuple(), (4, 1, 1));
+	/// ```
+	fn arrange(self, schemes: Vec<(String, Scheme)>) -> Result<HashMap<String, (Point, Rot, Scheme)>, Self::Error>;
 }
 
 /// [`Positioner`] with fully manual position management.
@@ -30,6 +80,7 @@ pub trait Positioner: Debug + Clone {
 #[allow(dead_code)]
 pub struct ManualPos {
 	poses: HashMap<String, (Option<Point>, Rot)>,
+	centered: HashSet<String>,
 	last_scheme: Option<String>,
 }
 
@@ -37,6 +88,7 @@ impl ManualPos {
 	pub fn new() -> Self {
 		ManualPos {
 			poses: HashMap::new(),
+			centered: HashSet::new(),
 			last_scheme: None
 		}
 	}
@@ -54,6 +106,42 @@ impl ManualPos {
 			.unwrap();
 
 		*pos = Some(pos_at);
+		self.centered.remove(&name);
+	}
+
+	/// Places scheme with equal name so that the center of its bounding
+	/// box (after rotation) is at the given position. If the size of the
+	/// scheme is even on some axis, center is shifted to the positive
+	/// side.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::util::Point;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_shapes_cube("line", (3, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_centered("line", (0, 0, 0));
+	///
+	/// let (scheme, _) = combiner.compile().unwrap();
+	/// assert_eq!(scheme.calculate_bounds().0, Point::new_ng(-1, 0, 0));
+	///
+	/// // Rotation is taken into account too
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_shapes_cube("line", (3, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.pos().place_centered("line", (0, 0, 0));
+	/// combiner.pos().rotate("line", (0, 0, 2));
+	///
+	/// let (scheme, _) = combiner.compile().unwrap();
+	/// assert_eq!(scheme.calculate_bounds().0, Point::new_ng(-1, 0, 0));
+	/// ```
+	pub fn place_centered<S, P>(&mut self, name: S, center: P)
+		where S: Into<String>,
+				P: Into<Point>
+	{
+		let name = name.into();
+		self.place(name.clone(), center);
+		self.centered.insert(name);
 	}
 
 	/// Places multiple schemes to the given position.
@@ -113,6 +201,36 @@ impl ManualPos {
 		}
 	}
 
+	/// Rotates given scheme, so that its shapes (which were not rotated
+	/// before) get given [`Facing`] and [`Orient`]. See
+	/// [`Rot::from_facing_orient`].
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use sm_logic::combiner::Combiner;
+	/// # use sm_logic::util::{Facing, Orient};
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add("gate", GateMode::AND).unwrap();
+	/// combiner.pos().place_last((0, 0, 0));
+	/// combiner.pos().rotate_facing("gate", Facing::PosX, Orient::Left);
+	///
+	/// let (scheme, _) = combiner.compile().unwrap();
+	/// let (_, rot, _) = &scheme.shapes()[0];
+	/// assert_eq!(rot.to_facing_orient(), (Facing::PosX, Orient::Left));
+	/// ```
+	pub fn rotate_facing<S>(&mut self, name: S, facing: Facing, orient: Orient)
+		where S: Into<String>,
+	{
+		self.rotate(name, Rot::from_facing_orient(facing, orient));
+	}
+
+	/// Same as [`ManualPos::rotate_facing`], but for the last added
+	/// scheme. If no schemes were added - panics.
+	pub fn rotate_last_facing(&mut self, facing: Facing, orient: Orient) {
+		self.rotate_last(Rot::from_facing_orient(facing, orient));
+	}
+
 	fn create_if_n_exists(&mut self, name: &String) {
 		if self.poses.get(name).is_none() {
 			self.poses.insert(
@@ -136,7 +254,25 @@ impl Positioner for ManualPos {
 		self.last_scheme = Some(scheme_name);
 	}
 
-	fn arrange(self, schemes: HashMap<String, Scheme>) -> Result<HashMap<String, (Point, Rot, Scheme)>, Self::Error> {
+	fn rename_scheme(&mut self, old: &str, new: &str) {
+		if let Some(pose) = self.poses.remove(old) {
+			self.poses.insert(new.to_string(), pose);
+		}
+
+		if self.centered.remove(old) {
+			self.centered.insert(new.to_string());
+		}
+
+		if self.last_scheme.as_deref() == Some(old) {
+			self.last_scheme = Some(new.to_string());
+		}
+	}
+
+	fn suggest_pos(&mut self, scheme_name: &str, pos: Point) {
+		self.place(scheme_name, pos);
+	}
+
+	fn arrange(self, schemes: Vec<(String, Scheme)>) -> Result<HashMap<String, (Point, Rot, Scheme)>, Self::Error> {
 		let mut posed_schemes: HashMap<String, (Point, Rot, Scheme)> = HashMap::new();
 
 		for (name, scheme) in schemes {
@@ -148,7 +284,13 @@ impl Positioner for ManualPos {
 						None => return Err(SchemeHasNoPosition { name }),
 
 						Some(pos) => {
-							posed_schemes.insert(name, (pos.clone(), rot.clone(), scheme));
+							let pos = if self.centered.contains(&name) {
+								centered_corner(*pos, rot, &scheme)
+							} else {
+								*pos
+							};
+
+							posed_schemes.insert(name, (pos, rot.clone(), scheme));
 						},
 					}
 			}
@@ -156,4 +298,20 @@ impl Positioner for ManualPos {
 
 		Ok(posed_schemes)
 	}
-}
\ No newline at end of file
+}
+
+/// Calculates position of the scheme, so that its center is at `center`
+fn centered_corner(center: Point, rot: &Rot, scheme: &Scheme) -> Point {
+	let rotated_size = rot.apply(scheme.bounds().cast());
+	let mut corner = center;
+
+	for axis in 0..3 {
+		let size = rotated_size[axis];
+		// If size is negative, scheme lies in the negative direction
+		// from its position.
+		let start = if size < 0 { size + 1 } else { 0 };
+		corner[axis] = center[axis] - size.abs() / 2 - start;
+	}
+
+	corner
+}
@@ -0,0 +1,40 @@
+//! Re-exports of the most commonly used types, so that a single
+//! `use sm_logic::prelude::*;` is enough to build most schemes.
+//!
+//! # Example
+//! ```
+//! use sm_logic::prelude::*;
+//!
+//! let mut combiner = Combiner::pos_manual();
+//!
+//! combiner.add("a", GateMode::OR).unwrap();
+//! combiner.pos().place_last((0, 0, 0));
+//! combiner.pos().rotate_last(Facing::PosX.to_rot());
+//!
+//! combiner.add("delay", Timer::new(10)).unwrap();
+//! combiner.pos().place_last((1, 0, 0));
+//!
+//! combiner.add("b", GateMode::AND).unwrap();
+//! combiner.pos().place_last((1, 0, 2));
+//!
+//! combiner.connect("a", "delay");
+//! combiner.custom("delay", "b", ConnStraight::new());
+//!
+//! let mut input = Bind::new("in", "logic", (1, 1, 1));
+//! input.connect_full("a");
+//! combiner.bind_input(input).unwrap();
+//! combiner.pass_output("out", "b", None as Option<String>).unwrap();
+//!
+//! let (scheme, invalid_acts): (Scheme, InvalidActs) = combiner.compile().unwrap();
+//! assert_eq!(invalid_acts.connections.len(), 0);
+//! assert_eq!(scheme.shapes_count(), 3);
+//! ```
+
+pub use crate::bind::Bind;
+pub use crate::combiner::{Combiner, InvalidActs};
+pub use crate::connection::{Connection, ConnDim, ConnFilter, ConnJoint, ConnMap, ConnStraight};
+pub use crate::positioner::{ManualPos, Positioner};
+pub use crate::scheme::Scheme;
+pub use crate::shape::Shape;
+pub use crate::shape::vanilla::{BlockBody, BlockType, Gate, GateMode, Timer};
+pub use crate::util::{Bounds, Facing, Orient, Point, Rot};
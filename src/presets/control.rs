@@ -0,0 +1,708 @@
+use crate::bind::Bind;
+use crate::combiner::Combiner;
+use crate::presets::{binary_selector, get_bit};
+use crate::scheme::Scheme;
+use crate::presets::convertors::priority_encoder;
+use crate::presets::memory::xor_mem_cell;
+use crate::shape::vanilla::{Button, GateMode, Timer};
+use crate::shape::vanilla::GateMode::*;
+use crate::util::{Facing, Point};
+
+/// ***Inputs***: a, b (phases of the encoder).
+///
+/// ***Outputs***: up, down.
+
+///
+/// Debounced decoder of quadrature (rotary encoder) signals. Every
+/// accepted change of phases makes 1-tick pulse on `up` or `down`
+/// output, depending on the direction of rotation.
+///
+/// ***Convention***: phase `a` leads phase `b` on clockwise rotation,
+/// so clockwise sequence of `(a, b)` is `00 -> 10 -> 11 -> 01 -> 00`
+/// and gives `up` pulses. Counterclockwise sequence is
+/// `00 -> 01 -> 11 -> 10 -> 00` and gives `down` pulses.
+///
+/// Changes of the phases are found by [`rising_edge`] and
+/// [`falling_edge`] detectors. Phases of rotating encoder change in
+/// turns, so [`xor_mem_cell`] remembers, which phase was accepted last,
+/// and further edges of the same phase are ignored, until the other
+/// phase changes. So bouncing contact gives only one pulse. The cost
+/// is that the first step after change of direction is lost. Edges
+/// of both phases in the same tick are ignored too.
+///
+/// Time complexity: 8 ticks.
+pub fn quadrature_decoder() -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::control::quadrature_decoder");
+	combiner.check_collisions();
+
+	// Bit 0 is set, if phase `a` was accepted last, bit 1 - if `b`
+	let last = xor_mem_cell(2);
+	let (last_start, last_size) = last.calculate_bounds();
+	combiner.add("last", last).unwrap();
+	combiner.pos().place_last(Point::new(0, 6, 0) - last_start);
+
+	for (i, (phase, other)) in [("a", "b"), ("b", "a")].into_iter().enumerate() {
+		let rise = format!("{}_rise", phase);
+		let fall = format!("{}_fall", phase);
+		let edge = format!("{}_edge", phase);
+		let edge_delayed = format!("{}_edge_delayed", phase);
+		let no_edge = format!("{}_no_edge", phase);
+		let free = format!("{}_free", phase);
+		let hold_0 = format!("{}_hold_0", phase);
+		let hold_1 = format!("{}_hold_1", phase);
+		let accept = format!("{}_accept", phase);
+
+		combiner.add(phase, OR).unwrap();
+		combiner.add(&rise, rising_edge()).unwrap();
+		combiner.add(&fall, falling_edge()).unwrap();
+		combiner.add_iter([
+			(&edge, OR),
+			(&edge_delayed, OR),
+			(&no_edge, NOR),
+			// Is off right after the phase was accepted, until
+			// the memory cell is written
+			(&free, NOR),
+			(&hold_0, OR),
+			(&hold_1, OR),
+			(&accept, AND),
+		]).unwrap();
+
+		combiner.connect_iter([phase], [&rise, &fall]);
+		combiner.connect_iter([&rise, &fall], [&edge]);
+		combiner.connect_iter([&edge], [&edge_delayed, &no_edge]);
+
+		combiner.connect(format!("last/_/{}", i), &free);
+		combiner.connect_iter([&edge_delayed, &accept, &hold_0, &hold_1], [&free]);
+		combiner.connect(&accept, &hold_0);
+		combiner.connect(&hold_0, &hold_1);
+
+		combiner.connect_iter([&edge_delayed, &free, &format!("{}_no_edge", other)], [&accept]);
+		combiner.connect_iter([&accept], ["last/write".to_string(), format!("last/data/{}", i)]);
+
+		let x = i as i32;
+		combiner.pos().place_iter([
+			(phase.to_string(), (x, 0, 0)),
+			(rise, (x, 1, 0)),
+			(fall, (x, 2, 0)),
+			(edge, (x, 3, 0)),
+			(edge_delayed, (x, 3, 1)),
+			(no_edge, (x, 3, 2)),
+			(free, (x, 4, 0)),
+			(hold_0, (x, 4, 1)),
+			(hold_1, (x, 4, 2)),
+			(accept, (x, 5, 0)),
+		]);
+
+		combiner.pass_input(phase, phase, Some("logic")).unwrap();
+	}
+
+	// Direction is given by the phases right after the accepted edge
+	combiner.add_iter([
+		("same", XNOR),
+		("different", XOR),
+		("a_up", AND),
+		("a_down", AND),
+		("b_up", AND),
+		("b_down", AND),
+		("up", OR),
+		("down", OR),
+	]).unwrap();
+
+	combiner.connect_iter(["a", "b"], ["same", "different"]);
+	combiner.connect_iter(["a_accept"], ["a_up", "a_down"]);
+	combiner.connect_iter(["b_accept"], ["b_up", "b_down"]);
+	combiner.connect_iter(["different"], ["a_up", "b_down"]);
+	combiner.connect_iter(["same"], ["a_down", "b_up"]);
+	combiner.connect_iter(["a_up", "b_up"], ["up"]);
+	combiner.connect_iter(["a_down", "b_down"], ["down"]);
+
+	let y = 6 + *last_size.y() as i32;
+	combiner.pos().place_iter([
+		("same", (0, y, 0)),
+		("different", (1, y, 0)),
+		("a_up", (0, y + 1, 0)),
+		("a_down", (1, y + 1, 0)),
+		("b_up", (0, y + 1, 1)),
+		("b_down", (1, y + 1, 1)),
+		("up", (0, y + 2, 0)),
+		("down", (1, y + 2, 0)),
+	]);
+
+	combiner.pass_output("up", "up", Some("logic")).unwrap();
+	combiner.pass_output("down", "down", Some("logic")).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: _ (1 bit).
+///
+/// ***Outputs***: _ (1 bit).
+
+///
+/// Rising edge detector. When the input turns on, the output gives
+/// a single pulse of exactly 1 tick width. Holding the input on does
+/// not give any more pulses.
+///
+/// Implemented as `AND(signal, NOT(delayed signal))`.
+///
+/// Time complexity: 2 ticks.
+pub fn rising_edge() -> Scheme {
+	edge_detector(OR, "presets::control::rising_edge")
+}
+
+/// ***Inputs***: _ (1 bit).
+///
+/// ***Outputs***: _ (1 bit).
+
+///
+/// Falling edge detector. When the input turns off, the output gives
+/// a single pulse of exactly 1 tick width.
+///
+/// Implemented as `AND(NOT(signal), delayed signal)`.
+///
+/// Time complexity: 2 ticks.
+pub fn falling_edge() -> Scheme {
+	edge_detector(NOR, "presets::control::falling_edge")
+}
+
+/// `signal` gate passes the input (OR) or inverts it (NOR), `prev`
+/// gate inverts `signal` one tick later. So both of them are active
+/// only during the 1 tick right after the edge.
+fn edge_detector(signal_mode: GateMode, debug_name: &str) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name(debug_name);
+
+	combiner.add_iter([
+		("signal", signal_mode),
+		("prev", NOR),
+		("edge", AND),
+	]).unwrap();
+
+	combiner.connect("signal", "prev");
+	combiner.connect_iter(["signal", "prev"], ["edge"]);
+
+	combiner.pass_input("_", "signal", Some("logic")).unwrap();
+	combiner.pass_output("_", "edge", Some("logic")).unwrap();
+
+	combiner.pos().place_iter([
+		("signal", (0, 0, 0)),
+		("prev", (0, 0, 1)),
+		("edge", (0, 0, 2)),
+	]);
+
+	combiner.check_collisions();
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: none.
+///
+/// ***Outputs***: _ (1 bit).
+
+///
+/// Clock (tick generator): gives 1-tick pulse every `period_ticks`
+/// ticks. Pulse circulates in a loop of OR gate and a timer.
+///
+/// ***Startup***: the loop is started by a single pulse, which is made
+/// of constant signal right after the creation is spawned (all the
+/// gates are off at that moment). First pulse of the output comes 3
+/// ticks after the spawn, then every `period_ticks`.
+///
+/// Panics if `period_ticks` is less than 2.
+pub fn clock(period_ticks: u32) -> Scheme {
+	if period_ticks < 2 {
+		panic!("Clock period must be at least 2 ticks, got {}", period_ticks);
+	}
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::control::clock");
+	combiner.check_collisions();
+
+	// Constant signal and single starting pulse
+	combiner.add_iter([
+		("const_0", AND),
+		("const_1", NOR),
+		("start_0", NOR),
+		("start_1", AND),
+		("loop", OR),
+	]).unwrap();
+	combiner.add("delay", Timer::new(period_ticks - 2)).unwrap();
+
+	combiner.connect("const_0", "const_1");
+	combiner.connect_iter(["const_1"], ["start_0", "start_1"]);
+	combiner.connect("start_0", "start_1");
+	combiner.connect("start_1", "loop");
+	combiner.connect("loop", "delay");
+	combiner.connect("delay", "loop");
+
+	combiner.pass_output("_", "loop", Some("logic")).unwrap();
+
+	combiner.pos().place_iter([
+		("const_0", (0, 0, 0)),
+		("const_1", (0, 0, 1)),
+		("start_0", (1, 0, 0)),
+		("start_1", (1, 0, 1)),
+		("loop", (2, 0, 0)),
+		("delay", (3, 0, 0)),
+	]);
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: none.
+///
+/// ***Outputs***: _ (1 bit).
+
+///
+/// Square wave clock: the output is on for `period_ticks / 2` ticks,
+/// then off for the same time. Made of NOR gate, which inverts its
+/// own delayed output.
+///
+/// ***Startup***: the loop is self-starting, since NOR gate turns on
+/// when all of its inputs are off. The output turns on 1 tick after
+/// the spawn.
+///
+/// Panics if `period_ticks` is odd or less than 2.
+pub fn clock_5050(period_ticks: u32) -> Scheme {
+	if period_ticks < 2 || period_ticks % 2 != 0 {
+		panic!("Square wave period must be even and at least 2 ticks, got {}", period_ticks);
+	}
+	let half_period = period_ticks / 2;
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::control::clock_5050");
+	combiner.check_collisions();
+
+	combiner.add("state", NOR).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+
+	if half_period == 1 {
+		combiner.connect("state", "state");
+	} else {
+		combiner.add("delay", Timer::new(half_period - 2)).unwrap();
+		combiner.pos().place_last((1, 0, 0));
+		combiner.connect("state", "delay");
+		combiner.connect("delay", "state");
+	}
+
+	combiner.pass_output("_", "state", Some("logic")).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: none.
+///
+/// ***Outputs***: 0_0, 1_0, etc. (`"{x}_{y}"`, one for each key),
+/// encoded (binary number of the pressed key), pressed.
+
+///
+/// Grid of `cols` by `rows` buttons. Every button has its own output,
+/// and `encoded` output gives number `y * cols + x` of the pressed key
+/// (see [`priority_encoder`]). If several keys are pressed at once,
+/// the one with the biggest number wins. `pressed` is active while at
+/// least one key is pressed.
+///
+/// Time complexity: 0 ticks for key outputs, 5 ticks for `encoded`.
+pub fn keypad(cols: u32, rows: u32) -> Scheme {
+	let keys_count = cols * rows;
+	if keys_count < 2 {
+		panic!("Keypad needs at least 2 keys (got {}x{})", cols, rows);
+	}
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::control::keypad");
+	combiner.check_collisions();
+
+	let encoder = priority_encoder(keys_count);
+	let (encoder_start, encoder_size) = encoder.calculate_bounds();
+	combiner.add("encoder", encoder).unwrap();
+	combiner.pos().place_last(Point::new(0, 0, -(*encoder_size.z() as i32)) - encoder_start);
+
+	for y in 0..rows {
+		for x in 0..cols {
+			let key = format!("{}_{}", x, y);
+			combiner.add(&key, Button::new()).unwrap();
+			combiner.pos().place_last((x as i32, y as i32, 0));
+
+			combiner.connect(&key, format!("encoder/_/{}", y * cols + x));
+			combiner.pass_output(&key, &key, Some("logic")).unwrap();
+		}
+	}
+
+	combiner.pass_output("encoded", "encoder", Some("binary")).unwrap();
+	combiner.pass_output("pressed", "encoder/valid", Some("logic")).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// Starts building of a clocked finite state machine with given
+/// amount of states and input bits. See [`FsmBuilder`].
+///
+/// # Example
+/// ```
+/// # use sm_logic::presets::control::fsm;
+/// // Toggles the state on each clock pulse, if input is active
+/// let mut toggle = fsm(2, 1);
+/// toggle.transition(0, "1", 1);
+/// toggle.transition(1, "1", 0);
+///
+/// let scheme = toggle.compile();
+/// assert!(scheme.output("state").is_some());
+/// ```
+pub fn fsm(states: u32, inputs: u32) -> FsmBuilder {
+	if states == 0 {
+		panic!("Finite state machine has to have at least one state.");
+	}
+
+	FsmBuilder {
+		states,
+		inputs,
+		transitions: vec![],
+	}
+}
+
+/// Builder of clocked finite state machine.
+///
+/// ***Inputs***: _ (input bits), clock.
+///
+/// ***Outputs***: state (binary), 0, 1, 2, etc... (one for each state).
+///
+/// Initial state is 0. On each 1-tick pulse of 'clock' input, the
+/// transition from the current state, pattern of which matches the
+/// input bits, is applied. If there is no such transition, state stays
+/// the same. Patterns of the transitions from the same state should not
+/// overlap.
+///
+/// State changes 4 ticks after the clock pulse, one-hot outputs are
+/// updated 3 ticks later. Next clock pulse should not come earlier than
+/// that. The same goes for the first pulse after the scheme is spawned.
+/// If clock, some input bit or some state has more than 255
+/// transitions, it is split with buffer gates, which adds a tick or two.
+///
+/// Internally, state is stored in XOR memory gates, that are toggled
+/// by the transitions, and decoded with `binary_selector`.
+#[derive(Debug, Clone)]
+pub struct FsmBuilder {
+	states: u32,
+	inputs: u32,
+	transitions: Vec<(u32, String, u32)>,
+}
+
+impl FsmBuilder {
+	/// Adds transition from `from` state to `to` state. `pattern` is
+	/// written just like a binary number, so its last character is the
+	/// input bit 0. Each character is '1', '0' or '-' (any value).
+	///
+	/// Will panic if the states do not exist or pattern is invalid.
+	pub fn transition(&mut self, from: u32, pattern: &str, to: u32) -> &mut Self {
+		if from >= self.states || to >= self.states {
+			panic!("Transition {} -> {} is invalid, there are only {} states.", from, to, self.states);
+		}
+
+		if pattern.len() != self.inputs as usize || pattern.chars().any(|c| !"01-".contains(c)) {
+			panic!("Invalid pattern '{}', it must consist of {} characters '0', '1' or '-'.", pattern, self.inputs);
+		}
+
+		self.transitions.push((from, pattern.to_string(), to));
+		self
+	}
+
+	pub fn compile(&self) -> Scheme {
+		let bits = ((self.states as f64).log2().ceil() as u32).max(1);
+		let inputs = self.inputs;
+		let mut combiner = Combiner::pos_manual();
+		combiner.set_debug_name("presets::control::fsm");
+		combiner.check_collisions();
+		combiner.auto_split_overflow();
+
+		// State register
+		combiner.add_shapes_cube("state", (bits, 1, 1), XOR, Facing::PosZ.to_rot()).unwrap();
+		combiner.add_shapes_cube("toggle", (bits, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+		combiner.connect("state", "state");
+		combiner.connect("toggle", "state");
+
+		// State decoding
+		let selector = binary_selector(bits);
+		let states_y = *selector.bounds().y() as i32 + 3;
+		combiner.add("selector", selector).unwrap();
+		combiner.add_shapes_cube("states", (self.states, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+		combiner.connect("state", "selector");
+
+		for state in 0..self.states {
+			combiner.connect(format!("selector/{}", state), format!("states/_/{}_0_0", state));
+			combiner.pass_output(state.to_string(), format!("states/_/{}_0_0", state), Some("logic")).unwrap();
+		}
+
+		combiner.add("clock", OR).unwrap();
+		combiner.pass_input("clock", "clock", Some("logic")).unwrap();
+
+		if inputs > 0 {
+			combiner.add_shapes_cube("input", (inputs, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+			combiner.add_shapes_cube("input_n", (inputs, 1, 1), NOR, Facing::PosZ.to_rot()).unwrap();
+
+			let mut input = Bind::new("_", "binary", (inputs, 1, 1));
+			input.connect_full("input");
+			input.connect_full("input_n");
+			input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+			combiner.bind_input(input).unwrap();
+		}
+
+		// Next state logic
+		for (i, (from, pattern, to)) in self.transitions.iter().enumerate() {
+			let name = format!("transition_{}", i);
+			combiner.add(&name, AND).unwrap();
+			combiner.pos().place_last((i as i32, states_y + 1, 0));
+
+			combiner.connect("clock", &name);
+			combiner.connect(format!("states/_/{}_0_0", from), &name);
+
+			for (bit, value) in pattern.chars().rev().enumerate() {
+				match value {
+					'1' => combiner.connect(format!("input/_/{}_0_0", bit), &name),
+					'0' => combiner.connect(format!("input_n/_/{}_0_0", bit), &name),
+					_ => {},
+				}
+			}
+
+			for bit in 0..bits {
+				if get_bit(*from as i64, bit) != get_bit(*to as i64, bit) {
+					combiner.connect(&name, format!("toggle/_/{}_0_0", bit));
+				}
+			}
+		}
+
+		let mut state = Bind::new("state", "binary", (bits, 1, 1));
+		state.connect_full("state");
+		state.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+		combiner.bind_output(state).unwrap();
+
+		combiner.pos().place_iter([
+			("clock", (0, 0, 0)),
+			("input", (1, 0, 0)),
+			("input_n", (1, 1, 0)),
+			("state", (0, 2, 0)),
+			("toggle", (0, 2, 1)),
+			("selector", (0, 3, 0)),
+			("states", (0, states_y, 0)),
+		]);
+
+		let (scheme, _invalid) = combiner.compile().unwrap();
+		scheme
+	}
+}
+
+#[test]
+fn quadrature_decoder_test() {
+	use crate::testing::Simulator;
+
+	// Sets phases for given counts of ticks, returns counts of pulses
+	fn apply(sim: &mut Simulator, phases: &[(bool, bool, u32)]) -> (u32, u32) {
+		let (mut ups, mut downs) = (0, 0);
+		for (a, b, ticks) in phases {
+			sim.set_input("a", &[*a]).unwrap();
+			sim.set_input("b", &[*b]).unwrap();
+
+			for _ in 0..*ticks {
+				sim.step();
+				ups += sim.output("up").unwrap()[0] as u32;
+				downs += sim.output("down").unwrap()[0] as u32;
+			}
+		}
+		(ups, downs)
+	}
+
+	let decoder = quadrature_decoder();
+	decoder.clone().to_json();
+
+	let clockwise = [(true, false), (true, true), (false, true), (false, false)];
+	let counterclockwise = [(false, true), (true, true), (true, false), (false, false)];
+
+	let mut sim = Simulator::new(&decoder);
+	sim.run(10);
+
+	for (a, b) in clockwise {
+		assert_eq!(apply(&mut sim, &[(a, b, 8)]), (1, 0));
+	}
+
+	// First step after change of direction is lost
+	assert_eq!(apply(&mut sim, &[(false, true, 10)]), (0, 0));
+	for (a, b) in &counterclockwise[1..] {
+		assert_eq!(apply(&mut sim, &[(*a, *b, 8)]), (0, 1));
+	}
+
+	// Bouncing contact gives a single pulse
+	let bounce = [(false, true, 1), (false, false, 1), (false, true, 1), (false, false, 1), (false, true, 10)];
+	assert_eq!(apply(&mut sim, &bounce), (0, 1));
+	let bounce = [(true, true, 2), (false, true, 2), (true, true, 3), (false, true, 2), (true, true, 10)];
+	assert_eq!(apply(&mut sim, &bounce), (0, 1));
+
+	// Both phases changing at once are ignored
+	assert_eq!(apply(&mut sim, &[(false, false, 10)]), (0, 0));
+}
+
+#[test]
+fn fsm_toggle_test() {
+	use crate::testing::Simulator;
+
+	let mut toggle = fsm(2, 1);
+	toggle.transition(0, "1", 1).transition(1, "1", 0);
+
+	let mut sim = Simulator::new(&toggle.compile());
+	// Let the state decoder settle
+	sim.run(10);
+
+	let pulse = |sim: &mut Simulator, input: bool| {
+		sim.set_input("_", &[input]).unwrap();
+		sim.set_input("clock", &[true]).unwrap();
+		sim.run(1);
+		sim.set_input("clock", &[false]).unwrap();
+		sim.run(10);
+	};
+
+	let mut expected = 0;
+	for input in [true, true, false, true, false, false, true] {
+		pulse(&mut sim, input);
+		if input {
+			expected = 1 - expected;
+		}
+
+		assert_eq!(sim.output_number("state").unwrap(), expected);
+		assert_eq!(sim.output("0").unwrap(), vec![expected == 0]);
+		assert_eq!(sim.output("1").unwrap(), vec![expected == 1]);
+	}
+}
+
+#[test]
+fn fsm_many_transitions_test() {
+	use crate::testing::Simulator;
+
+	// Counter: 300 transitions, all of them are connected to 'clock'
+	let mut counter = fsm(301, 0);
+	for state in 0..300 {
+		counter.transition(state, "", state + 1);
+	}
+
+	let mut sim = Simulator::new(&counter.compile());
+	sim.run(20);
+
+	for expected in 1..=3 {
+		sim.set_input("clock", &[true]).unwrap();
+		sim.run(1);
+		sim.set_input("clock", &[false]).unwrap();
+		sim.run(20);
+
+		assert_eq!(sim.output_number("state").unwrap(), expected);
+		assert_eq!(sim.output(expected.to_string()).unwrap(), vec![true]);
+	}
+}
+
+#[test]
+fn edge_detector_test() {
+	use crate::testing::Simulator;
+
+	for (scheme, pulse_on) in [(rising_edge(), true), (falling_edge(), false)] {
+		let mut sim = Simulator::new(&scheme);
+		sim.set_input("_", &[!pulse_on]).unwrap();
+		sim.run(10);
+		assert!(!sim.output("_").unwrap()[0]);
+
+		for _ in 0..3 {
+			sim.set_input("_", &[pulse_on]).unwrap();
+			let mut pulses = Vec::new();
+			for _ in 0..10 {
+				sim.step();
+				pulses.push(sim.output("_").unwrap()[0]);
+			}
+			// Exactly one tick wide, 2 ticks after the edge
+			let expected: Vec<bool> = (0..10).map(|i| i == 1).collect();
+			assert_eq!(pulses, expected);
+
+			// Opposite edge gives nothing
+			sim.set_input("_", &[!pulse_on]).unwrap();
+			for _ in 0..10 {
+				sim.step();
+				assert!(!sim.output("_").unwrap()[0]);
+			}
+		}
+	}
+}
+
+#[test]
+fn clock_test() {
+	use crate::testing::Simulator;
+
+	for period in [2, 3, 7] {
+		let mut sim = Simulator::new(&clock(period));
+
+		for tick in 1..40 {
+			sim.step();
+			let expected = tick >= 3 && (tick - 3) % period == 0;
+			assert_eq!(sim.output("_").unwrap(), vec![expected], "period {}, tick {}", period, tick);
+		}
+	}
+}
+
+#[test]
+fn clock_5050_test() {
+	use crate::testing::Simulator;
+
+	for period in [2, 4, 10] {
+		let mut sim = Simulator::new(&clock_5050(period));
+
+		for tick in 1..40 {
+			sim.step();
+			let expected = ((tick - 1) / (period / 2)) % 2 == 0;
+			assert_eq!(sim.output("_").unwrap(), vec![expected], "period {}, tick {}", period, tick);
+		}
+	}
+}
+
+#[test]
+fn keypad_test() {
+	use crate::testing::Simulator;
+
+	let keypad = keypad(3, 4);
+	keypad.clone().to_json();
+	assert_eq!(keypad.inputs().len(), 0);
+	assert_eq!(keypad.outputs().len(), 12 + 2);
+	assert_eq!(keypad.shape_histogram().get("button"), Some(&12));
+	assert!(keypad.output("2_3").is_some());
+
+	let mut sim = Simulator::new(&keypad);
+	sim.run(10);
+	assert_eq!(sim.output_number("encoded").unwrap(), 0);
+	assert_eq!(sim.output("pressed").unwrap(), vec![false]);
+
+	let button = |key: &str| keypad.output(key).unwrap().0.shape_map().as_raw()[0][0];
+
+	sim.press(button("1_2"), true);
+	sim.run(6);
+	assert_eq!(sim.output("1_2").unwrap(), vec![true]);
+	assert_eq!(sim.output_number("encoded").unwrap(), 2 * 3 + 1);
+	assert_eq!(sim.output("pressed").unwrap(), vec![true]);
+
+	// Key with the biggest number wins
+	sim.press(button("0_0"), true);
+	sim.press(button("2_3"), true);
+	sim.run(6);
+	assert_eq!(sim.output_number("encoded").unwrap(), 3 * 3 + 2);
+
+	sim.press(button("2_3"), false);
+	sim.run(6);
+	assert_eq!(sim.output_number("encoded").unwrap(), 2 * 3 + 1);
+
+	sim.press(button("0_0"), false);
+	sim.press(button("1_2"), false);
+	sim.run(6);
+	assert_eq!(sim.output_number("encoded").unwrap(), 0);
+	assert_eq!(sim.output("pressed").unwrap(), vec![false]);
+
+	// Used to overflow connections of the encoder
+	crate::presets::control::keypad(16, 16);
+}
+
@@ -1,12 +1,15 @@
 use std::collections::HashMap;
 use crate::bind::Bind;
 use crate::combiner::Combiner;
+use crate::connection::ConnDim;
+use crate::positioner::ManualPos;
+use crate::presets::{binary_selector, binary_selector_compact, connect_safe};
 use crate::presets::math::adder_mem;
 use crate::presets::shift_connection;
 use crate::scheme::Scheme;
 use crate::shape::vanilla::GateMode::*;
 use crate::shape::vanilla::Timer;
-use crate::util::Facing;
+use crate::util::{Facing, Point};
 
 // TODO: Make bindec_array slots 2D instead of 1D
 
@@ -237,6 +240,193 @@ pub fn add_3_if_more_th_4() -> Scheme {
 	scheme
 }
 
+/// ***Inputs***: _ (binary number).
+///
+/// ***Outputs***: all, 0, 1, 2, 3, etc... (one for each digit).
+
+///
+/// Converts binary numbers to numbers of given even `base`. Each
+/// digit is represented as `digits_per`-bit binary number. There is
+/// different output for each digit. Works the same way as
+/// [`bin_to_bindec`] (double dabble), but uses [`base_correction`]
+/// sections instead of [`add_3_if_more_th_4`] ones.
+///
+/// `bin_to_base(word_size, 4, 10)` is equivalent of bindec conversion,
+/// `bin_to_base(word_size, 4, 16)` gives hexadecimal digits and
+/// `bin_to_base(word_size, 3, 8)` gives octal ones. If base is exactly
+/// `2.pow(digits_per)`, no sections are needed and scheme is just
+/// a regrouping of input bits.
+///
+/// Time complexity: `O(word_size)`.
+/// Space complexity: `O(word_size.pow(2))`
+pub fn bin_to_base(word_size: u32, digits_per: u32, base: u32) -> Scheme {
+	if digits_per == 0 || digits_per > 16 {
+		panic!("Digit size must be from 1 to 16 bits (got {})", digits_per);
+	}
+	if base < 2 || base % 2 != 0 || base > (1 << digits_per) {
+		panic!("Base must be even and fit into {} bits (got {})", digits_per, base);
+	}
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::convertors::bin_to_base");
+
+	combiner.add_shapes_cube("input", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+	let mut input = Bind::new("_", "binary", (word_size, 1, 1));
+	input.connect_full("input");
+	input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input).unwrap();
+
+	let mut digits_count = 1;
+	let mut max_value = (1_u128 << word_size) - 1;
+	while max_value >= base as u128 {
+		max_value /= base as u128;
+		digits_count += 1;
+	}
+
+	// Every bit is kept at the position it will have in the result,
+	// `None` means that the bit is always 0.
+	let mut bits: Vec<Option<String>> = (0..(digits_count * digits_per + word_size))
+		.map(|x| if x < word_size { Some(format!("input/_/{}_0_0", x)) } else { None })
+		.collect();
+
+	let correction = ((1 << digits_per) - base) / 2;
+	let section = base_correction(digits_per, base);
+
+	// Before `shift`-th shift register holds `shift` highest bits
+	for shift in 0..word_size {
+		if correction == 0 {
+			break;
+		}
+
+		for digit in 0..digits_count {
+			let window_start = digit * digits_per + word_size - shift;
+			let window: Vec<Option<String>> = (0..digits_per)
+				.map(|bit| bits[(window_start + bit) as usize].clone())
+				.collect();
+
+			let max_value: u32 = window.iter()
+				.enumerate()
+				.filter(|(_, bit)| bit.is_some())
+				.map(|(i, _)| 1 << i)
+				.sum();
+
+			if max_value < base / 2 {
+				continue;
+			}
+
+			let name = format!("section_{}_{}", shift, digit);
+			combiner.add(&name, section.clone()).unwrap();
+			combiner.pos().place_last((window_start as i32, 0, shift as i32 + 1));
+
+			for (bit, from) in window.into_iter().enumerate() {
+				if let Some(from) = from {
+					combiner.connect(from, format!("{}/_/{}", name, bit));
+				}
+				bits[(window_start as usize) + bit] = Some(format!("{}/_/{}", name, bit));
+			}
+		}
+	}
+
+	let mut all_outputs = Bind::new("all", "digit_array", (digits_count * digits_per, 1, 1));
+	for digit in 0..digits_count {
+		let mut output = Bind::new(digit.to_string(), "digit", (digits_per, 1, 1));
+
+		for bit in 0..digits_per {
+			let global_bit = digit * digits_per + bit;
+			if let Some(path) = &bits[global_bit as usize] {
+				output.connect(((bit as i32, 0, 0), (1, 1, 1)), path);
+				all_outputs.connect(((global_bit as i32, 0, 0), (1, 1, 1)), path);
+			}
+		}
+
+		output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+		combiner.bind_output(output).unwrap();
+	}
+	combiner.bind_output(all_outputs).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: _.
+///
+/// ***Outputs***: _.
+
+///
+/// Just a section of for [`bin_to_base`] scheme - generalized version
+/// of [`add_3_if_more_th_4`]. If the input is binary number less than
+/// `base / 2`, output is the number. Otherwise - output is the number
+/// plus `(2.pow(digits_per) - base) / 2`. Inputs not less than `base`
+/// are not expected and give 0.
+///
+/// Time complexity: 4 ticks.
+///
+/// Will panic if `digits_per` is not in range `1..=16` or `base` is odd
+/// or does not fit into `digits_per` bits.
+pub fn base_correction(digits_per: u32, base: u32) -> Scheme {
+	if digits_per == 0 || digits_per > 16 {
+		panic!("Digit size must be from 1 to 16 bits (got {})", digits_per);
+	}
+	if base < 2 || base % 2 != 0 || base > (1 << digits_per) {
+		panic!("Base must be even and fit into {} bits (got {})", digits_per, base);
+	}
+
+	let correction = ((1 << digits_per) - base) / 2;
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::convertors::base_correction");
+
+	combiner.add_shapes_cube("positive", (digits_per, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("negative", (digits_per, 1, 1), NOR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place("positive", (0, 0, 0));
+	combiner.pos().place("negative", (0, 1, 0));
+	// Negative bits are not connected to input directly, so that unconnected
+	// input bits are still treated as zeroes
+	combiner.connect("positive", "negative");
+
+	// One AND gate for each possible non-zero digit
+	for value in 1..base {
+		let name = format!("value_{}", value);
+		combiner.add(&name, AND).unwrap();
+		combiner.pos().place_last((
+			((value - 1) % digits_per) as i32,
+			((value - 1) / digits_per) as i32 + 2,
+			0
+		));
+		combiner.pos().rotate_last(Facing::PosZ.to_rot());
+
+		for bit in 0..digits_per {
+			let source = if (value >> bit) & 1 == 1 { "positive" } else { "negative" };
+			combiner.connect(format!("{}/_/{}_0_0", source, bit), &name);
+		}
+
+		let result = if value < base / 2 { value } else { value + correction };
+		for bit in 0..digits_per {
+			if (result >> bit) & 1 == 1 {
+				combiner.connect(&name, format!("out/_/{}_0_0", bit));
+			}
+		}
+	}
+
+	let values_rows = (base - 2) / digits_per + 1;
+	combiner.add_shapes_cube("out", (digits_per, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, values_rows as i32 + 2, 0));
+
+	let mut input = Bind::new("_", "binary", (digits_per, 1, 1));
+	input.connect_full("positive");
+	input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input).unwrap();
+
+	let mut output = Bind::new("_", "binary", (digits_per, 1, 1));
+	output.connect_full("out");
+	output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(output).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
 /// ***Inputs***: start, all, 0, 1, 2, 3, etc... (one for each bindec digit).
 ///
 /// ***Outputs***: _.
@@ -310,4 +500,665 @@ pub fn bindec_to_bin(digits_count: u32) -> Scheme {
 
 	let (scheme, _invalid) = combiner.compile().unwrap();
 	scheme
-}
\ No newline at end of file
+}
+
+/// ***Inputs***: _ (2-bit binary number).
+///
+/// ***Outputs***: 0, 1, 2, 3 (one for each line), _ (all the lines).
+
+///
+/// Classic 2-to-4 line decoder: only the output line with the number
+/// of the input is active.
+///
+/// Time complexity: 2 ticks.
+pub fn decoder_2to4() -> Scheme {
+	line_decoder(2)
+}
+
+/// ***Inputs***: _ (3-bit binary number).
+///
+/// ***Outputs***: 0, 1, 2, ..., 7 (one for each line), _ (all the lines).
+
+///
+/// Classic 3-to-8 line decoder: only the output line with the number
+/// of the input is active.
+///
+/// Time complexity: 2 ticks.
+pub fn decoder_3to8() -> Scheme {
+	line_decoder(3)
+}
+
+fn line_decoder(word_size: u32) -> Scheme {
+	let lines_count = 1 << word_size;
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::convertors::line_decoder");
+
+	combiner.add_shapes_cube("positive", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("negative", (word_size, 1, 1), NOR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("lines", (lines_count, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+
+	for line in 0..lines_count {
+		for bit in 0..word_size {
+			let source = if (line >> bit) & 1 == 1 { "positive" } else { "negative" };
+			combiner.connect(format!("{}/_/{}_0_0", source, bit), format!("lines/_/{}_0_0", line));
+		}
+	}
+
+	let mut input = Bind::new("_", "binary", (word_size, 1, 1));
+	input.connect_full("positive");
+	input.connect_full("negative");
+	input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input).unwrap();
+
+	let mut output = Bind::new("_", "lines", (lines_count, 1, 1));
+	output.connect_full("lines");
+	output.gen_point_sectors("line", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(output).unwrap();
+
+	for line in 0..lines_count {
+		combiner.pass_output(line.to_string(), format!("lines/_/{}_0_0", line), Some("logic")).unwrap();
+	}
+
+	combiner.pos().place_iter([
+		("positive", (0, 0, 0)),
+		("negative", (0, 1, 0)),
+		("lines", (0, 2, 0)),
+	]);
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: 0, 1, 2, 3 (one for each line), _ (all the lines).
+///
+/// ***Outputs***: _ (2-bit binary number).
+
+///
+/// Classic 4-to-2 encoder: outputs the number of the active input line.
+/// Only one line is expected to be active at a time.
+///
+/// Time complexity: 2 ticks.
+pub fn encoder_4to2() -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::convertors::encoder_4to2");
+
+	combiner.add_shapes_cube("lines", (4, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("number", (2, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+
+	for line in 0..4 {
+		for bit in 0..2 {
+			if (line >> bit) & 1 == 1 {
+				combiner.connect(format!("lines/_/{}_0_0", line), format!("number/_/{}_0_0", bit));
+			}
+		}
+	}
+
+	let mut input = Bind::new("_", "lines", (4, 1, 1));
+	input.connect_full("lines");
+	input.gen_point_sectors("line", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input).unwrap();
+
+	for line in 0..4 {
+		combiner.pass_input(line.to_string(), format!("lines/_/{}_0_0", line), Some("logic")).unwrap();
+	}
+
+	let mut output = Bind::new("_", "binary", (2, 1, 1));
+	output.connect_full("number");
+	output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(output).unwrap();
+
+	combiner.pos().place_iter([
+		("lines", (0, 0, 0)),
+		("number", (0, 1, 0)),
+	]);
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: _ (`lines` lines, sectors are named '0', '1', etc.).
+///
+/// ***Outputs***: _ (binary number), valid.
+
+///
+/// Priority encoder: outputs the number of the highest active input
+/// line. `valid` is active, if at least one line is active (otherwise
+/// the number is zero).
+///
+/// Line is the highest active one, if it is active and none of the
+/// lines above it are. Lines are split into groups of about square root
+/// of `lines` lines: each line is only checked against higher lines of
+/// its group, and against activity of higher groups. So connections
+/// count stays low, and it works with hundreds of lines.
+///
+/// Time complexity: 5 ticks (one more tick of buffers, if there are
+/// more than 511 lines).
+pub fn priority_encoder(lines: u32) -> Scheme {
+	if lines < 2 {
+		panic!("Priority encoder needs at least 2 lines (got {})", lines);
+	}
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::convertors::priority_encoder");
+	// Bits of the number are fed by half of the lines each
+	combiner.auto_split_overflow();
+
+	let number_size = 32 - (lines - 1).leading_zeros();
+	let group_size = (lines as f64).sqrt().ceil() as u32;
+	let groups_count = lines.div_ceil(group_size);
+
+	combiner.add_shapes_cube("lines", (lines, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("group_any", (groups_count, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("highest", (lines, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("number", (number_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add("valid", OR).unwrap();
+
+	combiner.connect("lines", "highest");
+	combiner.dim("group_any", "valid", (true, true, true));
+
+	// Is active, if none of the groups above are
+	for group in 0..(groups_count - 1) {
+		let name = format!("no_higher_group_{}", group);
+		combiner.add(&name, NOR).unwrap();
+		combiner.pos().place_last((group as i32, 2, 0));
+
+		for higher in (group + 1)..groups_count {
+			combiner.connect(format!("group_any/_/{}_0_0", higher), &name);
+		}
+	}
+
+	for line in 0..lines {
+		let group = line / group_size;
+		let group_end = ((group + 1) * group_size).min(lines);
+		let highest = format!("highest/_/{}_0_0", line);
+
+		combiner.connect(format!("lines/_/{}_0_0", line), format!("group_any/_/{}_0_0", group));
+
+		// Is active, if none of the lines above in the group are
+		if line + 1 < group_end {
+			let name = format!("no_higher_line_{}", line);
+			combiner.add(&name, NOR).unwrap();
+			combiner.pos().place_last((line as i32, 3, 0));
+
+			for higher in (line + 1)..group_end {
+				combiner.connect(format!("lines/_/{}_0_0", higher), &name);
+			}
+			combiner.connect(&name, &highest);
+		}
+
+		if group + 1 < groups_count {
+			combiner.connect(format!("no_higher_group_{}", group), &highest);
+		}
+
+		for bit in 0..number_size {
+			if (line >> bit) & 1 == 1 {
+				combiner.connect(&highest, format!("number/_/{}_0_0", bit));
+			}
+		}
+	}
+
+	let mut input = Bind::new("_", "lines", (lines, 1, 1));
+	input.connect_full("lines");
+	input.gen_point_sectors("line", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input).unwrap();
+
+	let mut output = Bind::new("_", "binary", (number_size, 1, 1));
+	output.connect_full("number");
+	output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(output).unwrap();
+	combiner.pass_output("valid", "valid", Some("logic")).unwrap();
+
+	combiner.pos().place_iter([
+		("lines", (0, 0, 0)),
+		("group_any", (0, 1, 0)),
+		("highest", (0, 4, 0)),
+		("number", (0, 5, 0)),
+		("valid", (0, 6, 0)),
+	]);
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: _ (binary number).
+///
+/// ***Outputs***: _ (Gray code).
+
+///
+/// Converts binary number to Gray code: each bit of the result is XOR
+/// of the same and the next bit of the number.
+///
+/// Time complexity: 2 ticks.
+pub fn bin_to_gray(word_size: u32) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::convertors::bin_to_gray");
+
+	combiner.add_shapes_cube("input", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("gray", (word_size, 1, 1), XOR, Facing::PosZ.to_rot()).unwrap();
+
+	combiner.connect("input", "gray");
+	// Bit i + 1 goes to bit i
+	combiner.map("input", "gray", |(point, _), _|
+		if *point.x() > 0 { Some(point - Point::new_ng(1, 0, 0)) } else { None }
+	);
+
+	bind_bits(&mut combiner, "input", "gray", word_size);
+	combiner.pos().place_iter([
+		("input", (0, 0, 0)),
+		("gray", (0, 1, 0)),
+	]);
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: _ (Gray code).
+///
+/// ***Outputs***: _ (binary number).
+
+///
+/// Converts Gray code to binary number: each bit of the result is XOR
+/// of the same and all the higher bits of the code. All the bits are
+/// calculated in parallel.
+///
+/// Time complexity: 2 ticks.
+pub fn gray_to_bin(word_size: u32) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::convertors::gray_to_bin");
+
+	combiner.add_shapes_cube("input", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("binary", (word_size, 1, 1), XOR, Facing::PosZ.to_rot()).unwrap();
+
+	combiner.filter("input", "binary", ConnDim::new((true, false, false)), |start, end| start.x() >= end.x());
+
+	bind_bits(&mut combiner, "input", "binary", word_size);
+	combiner.pos().place_iter([
+		("input", (0, 0, 0)),
+		("binary", (0, 1, 0)),
+	]);
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: _ (binary number).
+///
+/// ***Outputs***: _ (`2^word_size` lines, sectors are named '0', '1', etc.).
+
+///
+/// Converts binary number to one-hot code: only the line with the
+/// number of the input is active. Made of `binary_selector`.
+///
+/// Time complexity: 3 ticks.
+pub fn bin_to_onehot(word_size: u32) -> Scheme {
+	let lines_count = 1 << word_size;
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::convertors::bin_to_onehot");
+
+	let selector = binary_selector(word_size);
+	let selector_x = *selector.bounds().x() as i32;
+	combiner.add("selector", selector).unwrap();
+	combiner.add_shapes_cube("onehot", (lines_count, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+
+	for line in 0..lines_count {
+		combiner.connect(format!("selector/{}", line), format!("onehot/_/{}_0_0", line));
+	}
+
+	combiner.pass_input("_", "selector", Some("binary")).unwrap();
+	let mut output = Bind::new("_", "onehot", (lines_count, 1, 1));
+	output.connect_full("onehot");
+	output.gen_point_sectors("line", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(output).unwrap();
+
+	combiner.pos().place_iter([
+		("selector", (0, 0, 0)),
+		("onehot", (selector_x, 0, 0)),
+	]);
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: 0, 1, 2, etc. (data, `inputs` numbers), select (binary
+/// number).
+///
+/// ***Outputs***: _ (binary number).
+
+///
+/// Multiplexer: passes the data input with the number from `select`
+/// input to the output. If `select` is not less than `inputs`, the
+/// output is zero.
+///
+/// Time complexity: 3 ticks from data inputs, 4 ticks from `select`.
+pub fn mux(word_size: u32, inputs: u32) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::convertors::mux");
+	combiner.check_collisions();
+
+	let select_size = select_size(inputs);
+	let selector = binary_selector_compact(select_size);
+	let (selector_start, selector_size) = selector.calculate_bounds();
+	combiner.add("selector", selector).unwrap();
+	combiner.pos().place_last(Point::new(-(*selector_size.x() as i32), 0, 0) - selector_start);
+	combiner.pass_input("select", "selector", Some("binary")).unwrap();
+
+	combiner.add_shapes_cube("output", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 2));
+
+	for k in 0..inputs {
+		let data = format!("data_{}", k);
+		let filter = format!("filter_{}", k);
+		combiner.add_shapes_cube(&data, (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, k as i32, 0));
+		combiner.add_shapes_cube(&filter, (word_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, k as i32, 1));
+
+		combiner.connect(&data, &filter);
+		combiner.connect(&filter, "output");
+
+		connect_safe(
+			&mut combiner,
+			(0..word_size).map(|bit| format!("{}/_/{}_0_0", filter, bit)),
+			|combiner, i| {
+				let name = format!("activator_{}_{}", k, i);
+				combiner.add(&name, AND).unwrap();
+				combiner.connect(format!("selector/{}", k), &name);
+				combiner.pos().place_last((word_size as i32 + i as i32, k as i32, 1));
+
+				name
+			},
+			None,
+			false
+		).unwrap();
+
+		let mut input = Bind::new(k.to_string(), "binary", (word_size, 1, 1));
+		input.connect_full(&data);
+		input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+		combiner.bind_input(input).unwrap();
+	}
+
+	let mut output = Bind::new("_", "binary", (word_size, 1, 1));
+	output.connect_full("output");
+	output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(output).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: _ (binary number), select (binary number).
+///
+/// ***Outputs***: 0, 1, 2, etc. (`outputs` binary numbers).
+
+///
+/// Demultiplexer: passes the input to the output with the number from
+/// `select` input. All the other outputs are zero.
+///
+/// Time complexity: 2 ticks from data input, 3 ticks from `select`.
+pub fn demux(word_size: u32, outputs: u32) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::convertors::demux");
+	combiner.check_collisions();
+
+	let select_size = select_size(outputs);
+	let selector = binary_selector_compact(select_size);
+	let (selector_start, selector_size) = selector.calculate_bounds();
+	combiner.add("selector", selector).unwrap();
+	combiner.pos().place_last(Point::new(-(*selector_size.x() as i32), 0, 0) - selector_start);
+	combiner.pass_input("select", "selector", Some("binary")).unwrap();
+
+	let mut input = Bind::new("_", "binary", (word_size, 1, 1));
+	input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+
+	for k in 0..outputs {
+		let filter = format!("filter_{}", k);
+		combiner.add_shapes_cube(&filter, (word_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, k as i32, 1));
+
+		connect_safe(
+			&mut combiner,
+			(0..word_size).map(|bit| format!("{}/_/{}_0_0", filter, bit)),
+			|combiner, i| {
+				let name = format!("activator_{}_{}", k, i);
+				combiner.add(&name, AND).unwrap();
+				combiner.connect(format!("selector/{}", k), &name);
+				combiner.pos().place_last((word_size as i32 + i as i32, k as i32, 1));
+
+				name
+			},
+			None,
+			false
+		).unwrap();
+
+		let mut output = Bind::new(k.to_string(), "binary", (word_size, 1, 1));
+		output.connect_full(&filter);
+		output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+		combiner.bind_output(output).unwrap();
+	}
+
+	// Every gate of the input can only feed MAX_CONNECTIONS filters
+	connect_safe(
+		&mut combiner,
+		(0..outputs).map(|k| format!("filter_{}", k)),
+		|combiner, i| {
+			let name = format!("input_{}", i);
+			combiner.add_shapes_cube(&name, (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+			combiner.pos().place_last((0, i as i32, 0));
+			input.connect_full(&name);
+
+			name
+		},
+		None,
+		false
+	).unwrap();
+	combiner.bind_input(input).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// Size of the binary number, that can select one of `count` items.
+fn select_size(count: u32) -> u32 {
+	((count as f64).log2().ceil() as u32).max(1)
+}
+
+/// Binds input and output of the same size with sectors for each bit.
+fn bind_bits(combiner: &mut Combiner<ManualPos>, input: &str, output: &str, word_size: u32) {
+	let mut input_bind = Bind::new("_", "binary", (word_size, 1, 1));
+	input_bind.connect_full(input);
+	input_bind.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input_bind).unwrap();
+
+	let mut output_bind = Bind::new("_", "binary", (word_size, 1, 1));
+	output_bind.connect_full(output);
+	output_bind.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(output_bind).unwrap();
+}
+
+#[test]
+fn line_decoders_test() {
+	use crate::testing::{Simulator, to_bits};
+
+	for (word_size, decoder) in [(2, decoder_2to4()), (3, decoder_3to8())] {
+		let lines_count = 1 << word_size;
+		assert_eq!(decoder.outputs().len(), lines_count + 1);
+		decoder.clone().to_json();
+
+		let mut sim = Simulator::new(&decoder);
+		for number in 0..lines_count {
+			sim.set_input_number("_", number as u64).unwrap();
+			sim.run(2);
+
+			assert_eq!(sim.output("_").unwrap(), to_bits(1 << number, lines_count as u32));
+			for line in 0..lines_count {
+				assert_eq!(sim.output(line.to_string()).unwrap(), vec![line == number]);
+			}
+		}
+	}
+}
+
+#[test]
+fn encoder_4to2_test() {
+	use crate::testing::Simulator;
+
+	let encoder = encoder_4to2();
+	assert_eq!(encoder.inputs().len(), 5);
+	encoder.clone().to_json();
+
+	let mut sim = Simulator::new(&encoder);
+	for line in 0..4 {
+		sim.set_input_number("_", 1 << line).unwrap();
+		sim.run(2);
+		assert_eq!(sim.output_number("_").unwrap(), line);
+	}
+
+	sim.set_input_number("_", 0).unwrap();
+	for line in 0..4 {
+		sim.set_input(line.to_string(), &[true]).unwrap();
+		sim.run(2);
+		assert_eq!(sim.output_number("_").unwrap(), line);
+		sim.set_input(line.to_string(), &[false]).unwrap();
+	}
+}
+
+#[test]
+fn priority_encoder_test() {
+	use crate::testing::{exhaustive_inputs, from_bits, Simulator};
+
+	let encoder = priority_encoder(5);
+	encoder.clone().to_json();
+	let mut sim = Simulator::new(&encoder);
+
+	for input in exhaustive_inputs(5) {
+		sim.set_input("_", &input).unwrap();
+		sim.run(5);
+
+		let lines = from_bits(&input);
+		let highest = if lines == 0 { 0 } else { 63 - lines.leading_zeros() as u64 };
+		assert_eq!(sim.output_number("_").unwrap(), highest, "{:05b}", lines);
+		assert_eq!(sim.output("valid").unwrap(), vec![lines != 0]);
+	}
+}
+
+#[test]
+fn priority_encoder_large_test() {
+	use crate::testing::Simulator;
+
+	for lines in [256, 1000] {
+		let encoder = priority_encoder(lines);
+		let mut sim = Simulator::new(&encoder);
+
+		for active in [vec![0], vec![3, 17], vec![255], vec![40, 200, 254], vec![lines - 1, 0]] {
+			let mut input = vec![false; lines as usize];
+			for line in &active {
+				input[*line as usize] = true;
+			}
+			sim.set_input("_", &input).unwrap();
+			sim.run(6);
+
+			let highest = *active.iter().max().unwrap() as u64;
+			assert_eq!(sim.output_number("_").unwrap(), highest, "{} lines", lines);
+			assert_eq!(sim.output("valid").unwrap(), vec![true]);
+		}
+	}
+}
+
+#[test]
+fn gray_code_test() {
+	use crate::testing::{exhaustive_inputs, from_bits, Simulator};
+
+	let mut to_gray = Simulator::new(&bin_to_gray(6));
+	let mut to_bin = Simulator::new(&gray_to_bin(6));
+
+	for input in exhaustive_inputs(6) {
+		let number = from_bits(&input);
+
+		to_gray.set_input("_", &input).unwrap();
+		to_gray.run(2);
+		let gray = to_gray.output("_").unwrap();
+		assert_eq!(from_bits(&gray), number ^ (number >> 1));
+
+		to_bin.set_input("_", &gray).unwrap();
+		to_bin.run(2);
+		assert_eq!(to_bin.output_number("_").unwrap(), number);
+	}
+}
+
+#[test]
+fn bin_to_onehot_test() {
+	use crate::testing::Simulator;
+
+	let mut sim = Simulator::new(&bin_to_onehot(4));
+
+	for number in 0..16 {
+		sim.set_input_number("_", number).unwrap();
+		sim.run(3);
+		assert_eq!(sim.output_number("_").unwrap(), 1 << number);
+		assert_eq!(sim.output(format!("_/{}", number)).unwrap(), vec![true]);
+	}
+}
+
+#[test]
+fn mux_demux_test() {
+	use crate::testing::Simulator;
+
+	let values = [5, 9, 12];
+
+	let mux = mux(4, 3);
+	mux.clone().to_json();
+	let mut sim = Simulator::new(&mux);
+	for (k, value) in values.into_iter().enumerate() {
+		sim.set_input_number(k.to_string(), value).unwrap();
+	}
+
+	for (k, value) in values.into_iter().enumerate() {
+		sim.set_input_number("select", k as u64).unwrap();
+		sim.run(4);
+		assert_eq!(sim.output_number("_").unwrap(), value);
+	}
+
+	// Out of range selection
+	sim.set_input_number("select", 3).unwrap();
+	sim.run(4);
+	assert_eq!(sim.output_number("_").unwrap(), 0);
+
+	let demux = demux(4, 3);
+	demux.clone().to_json();
+	let mut sim = Simulator::new(&demux);
+	sim.set_input_number("_", 11).unwrap();
+
+	for selected in 0..3 {
+		sim.set_input_number("select", selected).unwrap();
+		sim.run(3);
+		for k in 0..3 {
+			let expected = if k == selected { 11 } else { 0 };
+			assert_eq!(sim.output_number(k.to_string()).unwrap(), expected);
+		}
+	}
+}
+
+#[test]
+fn bin_to_base_test() {
+	use crate::testing::Simulator;
+
+	for (word_size, digits_per, base) in [(8, 4, 10), (7, 3, 8), (6, 4, 16), (7, 3, 6)] {
+		let scheme = bin_to_base(word_size, digits_per, base);
+		// Every output except 'all' is a digit
+		let digits = scheme.outputs().len() - 1;
+		let mut sim = Simulator::new(&scheme);
+
+		// Starting from the biggest number, so zero has to clear all the digits
+		for number in (0..(1 << word_size)).rev() {
+			sim.set_input_number("_", number).unwrap();
+			sim.run(word_size * 5 + 2);
+
+			let mut rest = number;
+			for digit in 0..digits {
+				let output = sim.output_number(digit.to_string()).unwrap();
+				assert_eq!(output, rest % base as u64, "digit {} of {} in base {}", digit, number, base);
+				rest /= base as u64;
+			}
+			assert_eq!(rest, 0, "{} does not fit in {} digits of base {}", number, digits, base);
+		}
+	}
+}
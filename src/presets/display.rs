@@ -1,10 +1,11 @@
 use std::collections::HashMap;
+use crate::bind::Bind;
 use crate::combiner::{Combiner, CompileError};
 use crate::presets::{binary_selector_compact};
 use crate::scheme::Scheme;
 use crate::shape::vanilla::{BlockBody, BlockType};
-use crate::shape::vanilla::GateMode::{AND, OR};
-use crate::util::Rot;
+use crate::shape::vanilla::GateMode::{AND, NOR, OR};
+use crate::util::{Facing, Point, Rot};
 include!(concat!(env!("OUT_DIR"), "/fonts_generated.rs"));
 
 #[derive(Debug, Clone)]
@@ -123,11 +124,48 @@ impl Font {
 			Err(error) => match error {
 				CompileError::PositionerError(error) => panic!("Font is not created: {:?}", error),
 				CompileError::ConnectionsOverflow { .. } => Err("Failed to create Font Scheme due to \
-				connections overflow. Fonts with more than 255 symbols are not fully supported.".to_string())
+				connections overflow. Fonts with more than 255 symbols are not fully supported.".to_string()),
+				CompileError::SchemesOverlap { pairs } => panic!("Font is not created: {:?} overlap", pairs),
 			}
 		}
 	}
 
+	/// ***Inputs***: 0_0, 1_0, etc. (`"{col}_{row}"`, symbol id for each
+	/// tile).
+	///
+	/// ***Outputs***: 0_0, 1_0, etc. (`"{col}_{row}"`, graphics of each
+	/// tile).
+	///
+	/// Text screen of `cols * rows` tiles. Each tile is a copy of
+	/// [`Font::make_scheme`] and shows its own symbol. Tile (0, 0) is
+	/// in the upper left corner.
+	pub fn make_text_display(&self, cols: u32, rows: u32) -> Result<Scheme, String> {
+		let tile = self.make_scheme()?;
+		let (tile_start, tile_size) = tile.calculate_bounds();
+
+		let mut combiner = Combiner::pos_manual();
+
+		for col in 0..cols {
+			for row in 0..rows {
+				let name = format!("{}_{}", col, row);
+				combiner.add(&name, tile.clone()).unwrap();
+				combiner.pos().place_last(Point::new(
+					0,
+					(col * tile_size.y()) as i32,
+					((rows - row - 1) * tile_size.z()) as i32
+				) - tile_start);
+
+				combiner.pass_input(&name, format!("{}/_", name), Some("binary")).unwrap();
+				combiner.pass_output(&name, format!("{}/_", name), Some("graphics")).unwrap();
+			}
+		}
+
+		match combiner.compile() {
+			Ok((scheme, _)) => Ok(scheme),
+			Err(error) => Err(format!("Failed to create text display: {:?}", error)),
+		}
+	}
+
 	pub fn make_sign_symb(&self, symbol: char, add_paddings: bool, fill_with: Scheme, bg_with: Scheme) -> Result<Scheme, String> {
 		if fill_with.bounds() != bg_with.bounds() {
 			return Err(format!("'fill_with' and 'bg_with' bounds must be equal ({:?} != {:?})", fill_with.bounds().tuple(), bg_with.bounds().tuple()));
@@ -223,4 +261,119 @@ pub fn numbers_font() -> Font {
 
 pub fn hex_font() -> Font {
 	Font::new(HEX, HEX_SYMBOLS, 3, 5).unwrap()
-}
\ No newline at end of file
+}
+
+/// Segments of seven-segment display, that are lit for each decimal
+/// digit. Segments are: a - top, b - upper right, c - lower right,
+/// d - bottom, e - lower left, f - upper left, g - middle.
+const SEVEN_SEGMENT_DIGITS: [&str; 10] = [
+	"abcdef", "bc", "abdeg", "abcdg", "bcfg",
+	"acdfg", "acdefg", "abc", "abcdefg", "abcdfg",
+];
+
+/// ***Inputs***: _ (bindec digit).
+///
+/// ***Outputs***: a, b, c, d, e, f, g (segments), _ (all the segments).
+
+///
+/// Decoder of bindec digit (see `bin_to_bindec`) for seven-segment
+/// display. Segments are: a - top, b - upper right, c - lower right,
+/// d - bottom, e - lower left, f - upper left, g - middle. If the
+/// input is not a digit (10..15), all the segments are off.
+///
+/// Each segment is a sum of products: the digit is decoded into one
+/// line per digit (AND gates), and each segment is OR of the lines of
+/// the digits, that light it.
+///
+/// Time complexity: 3 ticks.
+pub fn seven_segment() -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+
+	combiner.add_shapes_cube("positive", (4, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("negative", (4, 1, 1), NOR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("digits", (10, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("segments", (7, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+
+	for (digit, segments) in SEVEN_SEGMENT_DIGITS.iter().enumerate() {
+		for bit in 0..4 {
+			let source = if (digit >> bit) & 1 == 1 { "positive" } else { "negative" };
+			combiner.connect(format!("{}/_/{}_0_0", source, bit), format!("digits/_/{}_0_0", digit));
+		}
+
+		for segment in segments.chars() {
+			let segment_id = segment as u32 - 'a' as u32;
+			combiner.connect(format!("digits/_/{}_0_0", digit), format!("segments/_/{}_0_0", segment_id));
+		}
+	}
+
+	let mut input = Bind::new("_", "bindec", (4, 1, 1));
+	input.connect_full("positive");
+	input.connect_full("negative");
+	input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input).unwrap();
+
+	let mut output = Bind::new("_", "seven_segment", (7, 1, 1));
+	output.connect_full("segments");
+	for (i, segment) in ('a'..='g').enumerate() {
+		output.add_sector(segment.to_string(), (i as i32, 0, 0), (1, 1, 1), "logic").unwrap();
+		combiner.pass_output(segment.to_string(), format!("segments/_/{}_0_0", i), Some("logic")).unwrap();
+	}
+	combiner.bind_output(output).unwrap();
+
+	combiner.pos().place_iter([
+		("positive", (0, 0, 0)),
+		("negative", (0, 1, 0)),
+		("digits", (0, 2, 0)),
+		("segments", (0, 3, 0)),
+	]);
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+#[test]
+fn seven_segment_test() {
+	use crate::testing::Simulator;
+
+	let mut sim = Simulator::new(&seven_segment());
+
+	for digit in 0..16 {
+		sim.set_input_number("_", digit).unwrap();
+		sim.run(3);
+
+		let lit = SEVEN_SEGMENT_DIGITS.get(digit as usize).cloned().unwrap_or("");
+		for segment in 'a'..='g' {
+			assert_eq!(sim.output(segment.to_string()).unwrap(), vec![lit.contains(segment)], "{} {}", digit, segment);
+			assert_eq!(sim.output(format!("_/{}", segment)).unwrap(), vec![lit.contains(segment)]);
+		}
+	}
+}
+
+#[test]
+fn make_text_display_test() {
+	use crate::testing::Simulator;
+
+	let font = numbers_font();
+	let display = font.make_text_display(3, 2).unwrap();
+	assert_eq!(display.inputs().len(), 6);
+	assert_eq!(display.outputs().len(), 6);
+
+	let mut single = Simulator::new(&font.make_scheme().unwrap());
+	let mut sim = Simulator::new(&display);
+
+	let symbol = font.symbol_id('7').unwrap() as u64;
+	single.set_input_number("_", symbol).unwrap();
+	single.run(10);
+	sim.set_input_number("2_1", symbol).unwrap();
+	sim.run(10);
+
+	let expected = single.output("_").unwrap();
+	assert!(expected.iter().any(|pixel| *pixel));
+	assert_eq!(sim.output("2_1").unwrap(), expected);
+
+	// Other tiles still show symbol 0
+	single.set_input_number("_", 0).unwrap();
+	single.run(10);
+	assert_ne!(single.output("_").unwrap(), expected);
+	assert_eq!(sim.output("0_0").unwrap(), single.output("_").unwrap());
+}
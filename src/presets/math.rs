@@ -2,6 +2,8 @@ use crate::bind::Bind;
 use crate::combiner::Combiner;
 use crate::connection::{ConnMap};
 use crate::positioner::ManualPos;
+use crate::presets::convertors::priority_encoder;
+use crate::presets::memory::incomplete_xor_mem_cell;
 use crate::presets::{connect_safe, input_filter_rational, make_rational_bind, shapes_cube, shift_connection};
 use crate::scheme::Scheme;
 use crate::shape::vanilla::{BlockType, Timer};
@@ -250,9 +252,9 @@ pub fn big_multiplier(bits_before_point: u32, bits_after_point: u32) -> Scheme {
 		combiner.pos().place_last((-1, offset, i as i32));
 
 		let conn_offset = (i as i32) - (bits_after_point as i32);
-		combiner.custom("a", &name, ConnMap::new(
+		combiner.map("a", &name,
 			move |(point, _), _| Some(point + Point::new_ng(conn_offset, 0, 0))
-		));
+		);
 
 		combiner.dim(format!("b/_/{}_0_0", i), &name, (true, true, true));
 
@@ -519,6 +521,95 @@ pub fn inverter(word_size: u32) -> Scheme {
 }
 
 
+/// ***Inputs***: _ (number).
+///
+/// ***Outputs***: _ (negated number).
+
+///
+/// Negates a two's complement number (inverts all the bits and adds 1).
+///
+/// Is the same scheme as [`inverter`], which already computes two's
+/// complement, and exists to make signed arithmetic code readable.
+///
+/// ***Time complexity***: `O(word_size)` (at most `word_size + 2` ticks
+/// to settle).
+///
+/// ***Space complexity***: `O(word_size)` (exactly `4 * word_size + 2` gates).
+pub fn negate(word_size: u32) -> Scheme {
+	inverter(word_size)
+}
+
+/// ***Inputs***: _ (signed number).
+///
+/// ***Outputs***: _ (absolute value).
+
+///
+/// Computes absolute value of a two's complement number. Result is
+/// unsigned, so the smallest negative number (like `-128` for 8 bits)
+/// gives correct result too.
+///
+/// The number is negated by [`negate`], then the sign bit controls
+/// AND/OR multiplexer, that chooses between the number itself and its
+/// negation.
+///
+/// ***Time complexity***: `O(word_size)` (at most `word_size + 5` ticks
+/// to settle).
+///
+/// ***Space complexity***: `O(word_size)`.
+pub fn abs(word_size: u32) -> Scheme {
+	if word_size == 0 {
+		panic!("Cannot compute absolute value of 0 bit numbers");
+	}
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::math::abs");
+	combiner.check_collisions();
+
+	combiner.add_shapes_cube("number", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+
+	let sign_bit = format!("number/_/{}_0_0", word_size - 1);
+	combiner.add("positive", NOR).unwrap();
+	combiner.pos().place_last((0, 1, 0));
+	combiner.add("negative", OR).unwrap();
+	combiner.pos().place_last((1, 1, 0));
+	combiner.connect(&sign_bit, "positive");
+	combiner.connect(&sign_bit, "negative");
+
+	combiner.add_shapes_cube("keep", (word_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 1));
+	combiner.add_shapes_cube("take_negated", (word_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 1, 1));
+	combiner.add_shapes_cube("result", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 2));
+
+	let negated = negate(word_size);
+	let (start, _) = negated.calculate_bounds();
+	combiner.add("negate", negated).unwrap();
+	combiner.pos().place_last(Point::new(0, 2, 0) - start);
+
+	combiner.connect("number", "negate");
+	combiner.connect("number", "keep");
+	combiner.connect("negate", "take_negated");
+	combiner.dim("positive", "keep", (true, true, true));
+	combiner.dim("negative", "take_negated", (true, true, true));
+	combiner.connect_iter(["keep", "take_negated"], ["result"]);
+
+	let mut input = Bind::new("_", "binary", (word_size, 1, 1));
+	input.connect_full("number");
+	input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input).unwrap();
+
+	let mut output = Bind::new("_", "binary", (word_size, 1, 1));
+	output.connect_full("result");
+	output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(output).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+
 /// ***Inputs***: a, b, carry.
 ///
 /// ***Outputs***: _ (result), carry.
@@ -645,16 +736,12 @@ pub fn adder_compact(word_size: u32) -> Scheme {
 
 	let mut inp_a = Bind::new("a", "binary", (word_size, 1, 1));
 	inp_a.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
-	inp_a.connect_full("and_1");
-	inp_a.connect_full("and_2");
-	inp_a.connect_full("res");
+	inp_a.connect_full_iter(["and_1", "and_2", "res"]);
 	s.bind_input(inp_a).unwrap();
 
 	let mut inp_b = Bind::new("b", "binary", (word_size, 1, 1));
 	inp_b.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
-	inp_b.connect_full("and_2");
-	inp_b.connect_full("and_3");
-	inp_b.connect_full("res");
+	inp_b.connect_full_iter(["and_2", "and_3", "res"]);
 	s.bind_input(inp_b).unwrap();
 
 	let mut out = Bind::new("_", "binary", (word_size, 1, 1));
@@ -668,9 +755,9 @@ pub fn adder_compact(word_size: u32) -> Scheme {
 	s.bind_input(carry_in).unwrap();
 
 	let mut carry_out = Bind::new("carry", "bit", (1, 1, 1));
-	carry_out.connect_full(format!("and_1/_/{}_0_0", word_size as i32 - 1))
-			.connect_full(format!("and_2/_/{}_0_0", word_size as i32 - 1))
-			.connect_full(format!("and_3/_/{}_0_0", word_size as i32 - 1));
+	carry_out.connect_full_iter(
+		["and_1", "and_2", "and_3"].map(|and| format!("{}/_/{}_0_0", and, word_size as i32 - 1))
+	);
 	s.bind_output(carry_out).unwrap();
 
 	let (scheme, _invalid) = s.compile().unwrap();
@@ -1005,56 +1092,818 @@ pub fn fast_compare(word_size: u32) -> Scheme {
 	scheme
 }
 
-// Divide algo
+/// ***Inputs***: a, b.
+///
+/// ***Outputs***: min, max.
 
-//	Set remainder to a
-// For i in 0..word_size
-// if b << i > rem
-// 		rem -= b << i
-//		result |= 1 << i
+///
+/// Sorts two unsigned binary numbers: the smaller one goes to 'min'
+/// output, the bigger one - to 'max'.
+///
+/// Numbers are compared by [`fast_compare`], its 'a>b' output controls
+/// AND/OR multiplexers, that route either 'a' or 'b' to each output.
+/// Inputs are delayed by timers to reach the multiplexers at the same
+/// time as the comparison result.
+///
+/// Does not allow for threaded calculations.
+///
+/// ***Time complexity***: `O(1)` (exactly `9` ticks: 1 tick for input
+/// gates, 5 ticks for `fast_compare` with its own input gates, 1 tick
+/// for selector gates and 2 ticks for the multiplexers).
+///
+/// ***Space complexity***: `O(word_size)`.
+pub fn min_max(word_size: u32) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::math::min_max");
+	combiner.check_collisions();
 
-pub fn divider(bits_before_point: u32, bits_after_point: u32) -> Scheme {
+	let compare = fast_compare(word_size);
+	let (start, _) = compare.calculate_bounds();
+	combiner.add("compare", compare).unwrap();
+	combiner.pos().place_last(Point::new(0, 0, 0) - start);
+
+	let z = 3;
+	combiner.add_shapes_cube("a", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, z));
+	combiner.add_shapes_cube("b", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 1, z));
+
+	// Selector: "a_bigger" enables 'a' to go to 'max', "b_bigger" (which
+	// is actually "a is not bigger") enables 'a' to go to 'min'
+	combiner.add("a_bigger", OR).unwrap();
+	combiner.pos().place_last((0, 2, z));
+	combiner.add("b_bigger", NOR).unwrap();
+	combiner.pos().place_last((1, 2, z));
+	combiner.connect("compare/a>b", "a_bigger");
+	combiner.connect("compare/a>b", "b_bigger");
+
+	combiner.connect("a", "compare/a");
+	combiner.connect("b", "compare/b");
+
+	for (i, name) in ["min_a", "min_b", "max_a", "max_b"].into_iter().enumerate() {
+		combiner.add_shapes_cube(name, (word_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, i as i32, z + 3));
+	}
+
+	let delay_a = combiner.connect_delayed("a", "min_a", 5).unwrap();
+	combiner.pos().place_last((0, 0, z + 1));
+	combiner.connect(&delay_a, "max_a");
+
+	let delay_b = combiner.connect_delayed("b", "min_b", 5).unwrap();
+	combiner.pos().place_last((0, 1, z + 1));
+	combiner.connect(&delay_b, "max_b");
+
+	combiner.dim("a_bigger", "max_a", (true, true, true));
+	combiner.dim("a_bigger", "min_b", (true, true, true));
+	combiner.dim("b_bigger", "min_a", (true, true, true));
+	combiner.dim("b_bigger", "max_b", (true, true, true));
+
+	combiner.add_shapes_cube("min", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, z + 4));
+	combiner.add_shapes_cube("max", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 1, z + 4));
+
+	combiner.connect_iter(["min_a", "min_b"], ["min"]);
+	combiner.connect_iter(["max_a", "max_b"], ["max"]);
+
+	for name in ["a", "b"] {
+		let mut input = Bind::new(name, "binary", (word_size, 1, 1));
+		input.connect_full(name);
+		input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+		combiner.bind_input(input).unwrap();
+	}
+
+	for name in ["min", "max"] {
+		let mut output = Bind::new(name, "binary", (word_size, 1, 1));
+		output.connect_full(name);
+		output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+		combiner.bind_output(output).unwrap();
+	}
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: a, b.
+///
+/// ***Outputs***: and, or, xor, not_a.
+
+///
+/// Computes bitwise AND, OR and XOR of two binary numbers and bitwise
+/// NOT of the number 'a'. Each output is a separate cube of gates fed
+/// by the input buses bit to bit.
+///
+/// Allows for 1-tick threaded calculations.
+///
+/// ***Time complexity***: `O(1)` (exactly `2` ticks).
+///
+/// ***Space complexity***: `O(word_size)` (exactly `6 * word_size` gates).
+pub fn bitwise(word_size: u32) -> Scheme {
 	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::math::bitwise");
 
-	let _thread_delay = 4;
-	let word_size = bits_before_point + bits_after_point;
+	combiner.add_shapes_cube("a", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+	combiner.add_shapes_cube("b", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 1, 0));
 
-	combiner.add("a", input_filter_rational(bits_before_point, bits_after_point)).unwrap();
-	combiner.pass_input("a", "a", None as Option<String>).unwrap();
-	combiner.pass_input("a_rational", "a/rational", None as Option<String>).unwrap();
+	let outputs = [("and", AND), ("or", OR), ("xor", XOR), ("not_a", NOR)];
+	for (i, (name, mode)) in outputs.into_iter().enumerate() {
+		combiner.add_shapes_cube(name, (word_size, 1, 1), mode, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, i as i32, 1));
 
-	combiner.add("b", input_filter_rational(bits_before_point, bits_after_point)).unwrap();
-	combiner.pass_input("b", "b", None as Option<String>).unwrap();
-	combiner.pass_input("b_rational", "b/rational", None as Option<String>).unwrap();
+		combiner.connect("a", name);
+		if name != "not_a" {
+			combiner.connect("b", name);
+		}
 
-	combiner.add("remainder", adder_compact(word_size)).unwrap();
-	{
-		// combiner.add("rem_reset", );
-		combiner.line_rot_mul(["rem_cycle_1", "rem_cycle_2"], AND, word_size).unwrap();
+		let mut output = Bind::new(name, "binary", (word_size, 1, 1));
+		output.connect_full(name);
+		output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+		combiner.bind_output(output).unwrap();
+	}
+
+	for name in ["a", "b"] {
+		let mut input = Bind::new(name, "binary", (word_size, 1, 1));
+		input.connect_full(name);
+		input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+		combiner.bind_input(input).unwrap();
+	}
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: clock, load, seed.
+///
+/// ***Outputs***: _ (state).
+
+///
+/// Linear feedback shift register - simple pseudo-random generator.
+///
+/// On each 1-tick signal to 'clock' the state is shifted by one bit
+/// towards the higher bits, and the lowest bit becomes XOR of the state
+/// bits with indices from `taps`. To set the state send it to 'seed'
+/// and 1-tick signal to 'load' simultaneously.
+///
+/// State is stored in `incomplete_xor_mem_cell` with two write modules:
+/// one for shifting, one for loading.
+///
+/// All-zero state never changes, and it is the initial state, so the
+/// seed must be loaded first. With `taps` like `[3, 2]` for 4 bits or
+/// `[7, 5, 4, 3]` for 8 bits the state goes through all `2^word_size - 1`
+/// non-zero values.
+///
+/// Timing: clock signals must be at least 4 ticks apart, the state is
+/// updated 3 ticks after the clock or load signal.
+///
+/// Will panic if `taps` is empty or any of them is not less than
+/// `word_size`.
+pub fn lfsr(word_size: u32, taps: &[u32]) -> Scheme {
+	if taps.is_empty() {
+		panic!("LFSR needs at least one tap");
+	}
+	if let Some(tap) = taps.iter().find(|tap| **tap >= word_size) {
+		panic!("LFSR tap {} is out of word size {}", tap, word_size);
+	}
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::math::lfsr");
+	combiner.check_collisions();
+
+	let cell = incomplete_xor_mem_cell(word_size, 2);
+	let (start, size) = cell.calculate_bounds();
+	combiner.add("cell", cell).unwrap();
+	combiner.pos().place_last(Point::new_ng(0, 0, 0) - start);
 
-		combiner.connect("remainder", "rem_cycle_1");
-		combiner.connect("rem_cycle_1", "rem_cycle_2");
-		combiner.connect("rem_cycle_2", "remainder/b");
+	let y = *size.y() as i32;
+	combiner.add_iter([("clock", OR), ("load", OR), ("feedback", XOR)]).unwrap();
+	combiner.pos().place_iter([
+		("clock", (0, y, 0)),
+		("load", (1, y, 0)),
+		("feedback", (2, y, 0)),
+	]);
+
+	if combiner.broadcast("clock", "cell/write_0").unwrap().is_some() {
+		combiner.pos().place_last((0, y + 1, 0));
+	}
+	if combiner.broadcast("load", "cell/write_1").unwrap().is_some() {
+		combiner.pos().place_last((0, y + 2, 0));
 	}
 
-	combiner.add("inverter", inverter(word_size)).unwrap();
-	combiner.add("compare", fast_compare(word_size)).unwrap();
+	// Shifted state is the data for the shifting write module
+	combiner.custom("cell", "cell/data_0", shift_connection((1, 0, 0)));
+	for tap in taps {
+		combiner.connect(format!("cell/_/{}", tap), "feedback");
+	}
+	combiner.connect("feedback", "cell/data_0/0");
+
+	combiner.pass_input("clock", "clock", Some("logic")).unwrap();
+	combiner.pass_input("load", "load", Some("logic")).unwrap();
+	combiner.pass_input("seed", "cell/data_1", Some("binary")).unwrap();
+	combiner.pass_output("_", "cell", Some("binary")).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
 
-	let activators_count = ((word_size + MAX_CONNECTIONS - 1) / MAX_CONNECTIONS) as i32;
+/// ***Inputs***: up, down.
+///
+/// ***Outputs***: _ (value).
+
+///
+/// Binary up/down counter. 1-tick signal to 'up' increases the value
+/// by one, 1-tick signal to 'down' decreases it. Value wraps around on
+/// overflow, initial value is 0.
+///
+/// Value is stored in XOR memory gates. Bit `i` is toggled on 'up', if
+/// all the lower bits are ones, and on 'down', if all of them are
+/// zeros.
+///
+/// Timing: the value is updated 3 ticks after the signal. Signals must
+/// be at least 4 ticks apart, 'up' and 'down' must not be sent in the
+/// same tick.
+pub fn counter(word_size: u32) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::math::counter");
+	combiner.check_collisions();
+
+	combiner.add_shapes_cube("value", (word_size, 1, 1), XOR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("value_n", (word_size, 1, 1), NOR, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("inc", (word_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_shapes_cube("dec", (word_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+	combiner.add_iter([("up", OR), ("down", OR)]).unwrap();
+
+	combiner.connect("value", "value");
+	combiner.connect("value", "value_n");
+	combiner.dim("up", "inc", (true, true, true));
+	combiner.dim("down", "dec", (true, true, true));
+	combiner.connect_iter(["inc", "dec"], ["value"]);
+
+	for bit in 0..word_size {
+		for lower in 0..bit {
+			combiner.connect(format!("value/_/{}_0_0", lower), format!("inc/_/{}_0_0", bit));
+			combiner.connect(format!("value_n/_/{}_0_0", lower), format!("dec/_/{}_0_0", bit));
+		}
+	}
 
 	combiner.pos().place_iter([
-		("a", (0, -activators_count, 0)),
-		("b", (0, -activators_count, 1)),
-		("remainder", (3, 0, 0)),
-		("rem_cycle_1", (5, 0, 1)),
-		("rem_cycle_2", (2, 0, 1)),
+		("value", (0, 0, 0)),
+		("value_n", (0, 1, 0)),
+		("inc", (0, 2, 0)),
+		("dec", (0, 3, 0)),
+		("up", (0, 4, 0)),
+		("down", (1, 4, 0)),
 	]);
 
-	combiner.pos().rotate_iter(
-		["rem_cycle_1", "rem_cycle_2"]
-			.map(|x| (x, (0, 0, 1)))
-	);
+	combiner.pass_input("up", "up", Some("logic")).unwrap();
+	combiner.pass_input("down", "down", Some("logic")).unwrap();
+
+	let mut value = Bind::new("_", "binary", (word_size, 1, 1));
+	value.connect_full("value");
+	value.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(value).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// Direction of the shift for [`barrel_shifter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShiftDir {
+	/// Towards the higher bits (multiplication by power of 2).
+	Left,
+	/// Towards the lower bits (division by power of 2).
+	Right,
+}
+
+/// ***Inputs***: data, amount.
+///
+/// ***Outputs***: _ (shifted data).
+
+///
+/// Logically shifts `data` by `amount` bits in given direction. Freed
+/// bits are filled with zeros, if `amount` is not less than
+/// `word_size`, result is zero.
+///
+/// Consists of `amount` size stages, stage `k` chooses between the
+/// number shifted by `2^k` and the number itself, depending on the
+/// bit `k` of `amount`.
+///
+/// `amount` has `log2(word_size)` bits (rounded up).
+///
+/// ***Time complexity***: `O(log(word_size))` (exactly
+/// `2 * amount_size + 1` ticks, plus 1 tick if `word_size` is bigger
+/// than [`MAX_CONNECTIONS`]).
+///
+/// ***Space complexity***: `O(word_size * log(word_size))`.
+pub fn barrel_shifter(word_size: u32, direction: ShiftDir) -> Scheme {
+	if word_size < 2 {
+		panic!("Barrel shifter needs at least 2 bits to shift (got {})", word_size);
+	}
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::math::barrel_shifter");
+	combiner.check_collisions();
+
+	let amount_size = 32 - (word_size - 1).leading_zeros();
+
+	combiner.add_shapes_cube("data_0", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+	combiner.add_shapes_cube("amount_pos", (amount_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 1));
+	combiner.add_shapes_cube("amount_neg", (amount_size, 1, 1), NOR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 2));
+
+	for stage in 0..amount_size {
+		let shift = 1 << stage;
+		let y = stage as i32 * 3;
+
+		let data = format!("data_{}", stage);
+		let keep = format!("keep_{}", stage);
+		let shifted = format!("shifted_{}", stage);
+		let next = format!("data_{}", stage + 1);
+
+		combiner.add_shapes_cube(&keep, (word_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, y + 1, 0));
+		// Only the bits, that receive data from the shifted number
+		combiner.add_shapes_cube(&shifted, (word_size - shift, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+		combiner.add_shapes_cube(&next, (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, y + 3, 0));
+
+		combiner.connect(&data, &keep);
+		combiner.connect(&keep, &next);
+
+		match direction {
+			ShiftDir::Left => {
+				combiner.pos().place(&shifted, (shift as i32, y + 2, 0));
+				combiner.connect(&data, &shifted);
+				combiner.custom(&shifted, &next, shift_connection((shift as i32, 0, 0)));
+			}
+
+			ShiftDir::Right => {
+				combiner.pos().place(&shifted, (0, y + 2, 0));
+				combiner.custom(&data, &shifted, shift_connection((-(shift as i32), 0, 0)));
+				combiner.connect(&shifted, &next);
+			}
+		}
+
+		let amount_neg = format!("amount_neg/_/{}_0_0", stage);
+		if combiner.broadcast(&amount_neg, &keep).unwrap().is_some() {
+			combiner.pos().place_last((0, y + 1, 1));
+		}
+
+		let amount_pos = format!("amount_pos/_/{}_0_0", stage);
+		if combiner.broadcast(&amount_pos, &shifted).unwrap().is_some() {
+			combiner.pos().place_last((0, y + 2, 1));
+		}
+	}
+
+	let mut data = Bind::new("data", "binary", (word_size, 1, 1));
+	data.connect_full("data_0");
+	data.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(data).unwrap();
+
+	let mut amount = Bind::new("amount", "binary", (amount_size, 1, 1));
+	amount.connect_full("amount_pos");
+	amount.connect_full("amount_neg");
+	amount.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(amount).unwrap();
+
+	let mut output = Bind::new("_", "binary", (word_size, 1, 1));
+	output.connect_full(format!("data_{}", amount_size));
+	output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(output).unwrap();
 
 	let (scheme, _invalid) = combiner.compile().unwrap();
 	scheme
 }
+
+/// ***Inputs***: _ (binary number).
+///
+/// ***Outputs***: mantissa, shift.
+
+///
+/// Normalizes the number: shifts it to the left, until its highest
+/// set bit becomes the highest bit of the word. `mantissa` is the
+/// shifted number, and `shift` is the amount of the shift (which is
+/// the count of leading zeros). Zero gives zero mantissa and zero
+/// shift.
+///
+/// Leading bit is found by [`priority_encoder`]. Bits are fed to lines
+/// of the encoder, so that the highest bit of the word comes to its
+/// highest line, then the shift is the inverted number of the line.
+/// Then the number is shifted by [`barrel_shifter`].
+///
+/// `shift` has `log2(word_size)` bits (rounded up).
+///
+/// ***Time complexity***: `O(log(word_size))` (at most
+/// `2 * shift_size + 8` ticks).
+pub fn normalize(word_size: u32) -> Scheme {
+	if word_size < 2 {
+		panic!("Normalization needs at least 2 bits (got {})", word_size);
+	}
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::math::normalize");
+	combiner.check_collisions();
+
+	let shift_size = 32 - (word_size - 1).leading_zeros();
+	// Encoder has all the lines, that `shift_size` bits can address
+	let lines = 1 << shift_size;
+
+	combiner.add_shapes_cube("input", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+
+	let encoder = priority_encoder(lines);
+	let (encoder_start, encoder_size) = encoder.calculate_bounds();
+	combiner.add("encoder", encoder).unwrap();
+	combiner.pos().place_last(Point::new(0, 1, 0) - encoder_start);
+
+	// Bit of the line number is never set without `valid`, so XOR
+	// inverts it, and keeps zero shift for zero input
+	let shift_y = 1 + *encoder_size.y() as i32;
+	combiner.add_shapes_cube("shift", (shift_size, 1, 1), XOR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, shift_y, 0));
+
+	for bit in 0..word_size {
+		let line = bit + lines - word_size;
+		combiner.connect(format!("input/_/{}_0_0", bit), format!("encoder/_/{}", line));
+	}
+	combiner.connect("encoder", "shift");
+	combiner.dim("encoder/valid", "shift", (true, true, true));
+
+	let shifter = barrel_shifter(word_size, ShiftDir::Left);
+	let (shifter_start, _) = shifter.calculate_bounds();
+	combiner.add("shifter", shifter).unwrap();
+	combiner.pos().place_last(Point::new(0, shift_y + 1, 0) - shifter_start);
+
+	combiner.connect("input", "shifter/data");
+	combiner.connect("shift", "shifter/amount");
+
+	let mut input = Bind::new("_", "binary", (word_size, 1, 1));
+	input.connect_full("input");
+	input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input).unwrap();
+
+	combiner.pass_output("mantissa", "shifter", Some("binary")).unwrap();
+
+	let mut shift = Bind::new("shift", "binary", (shift_size, 1, 1));
+	shift.connect_full("shift");
+	shift.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(shift).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: a, a_rational,
+/// b, b_rational.
+///
+/// ***Outputs***: _ (quotient), rational, remainder.
+
+///
+/// Divides unsigned number 'a' by 'b'. Combinational - after the
+/// inputs are set, quotient and remainder are available a while later
+/// and follow the inputs.
+///
+/// Implements restoring long division: the dividend `a << bits_after_point`
+/// is processed bit by bit from the highest one. On each step next bit
+/// is appended to the partial remainder and `b` is subtracted from it
+/// (by adding `-b`, which is computed by `inverter`), if the remainder
+/// is not less than `b`. Each step is a separate `adder`.
+///
+/// Quotient is truncated to `word_size` bits. 'remainder' is the raw
+/// remainder of the long division, which equals
+/// `(a - quotient * b) * 2^(2 * bits_after_point)`. So for integers
+/// (`bits_after_point == 0`) it is just `a % b`. Division by zero gives
+/// zero quotient.
+///
+/// ***Time complexity***: `O(word_size * (word_size + bits_after_point))`
+/// (each of `word_size + bits_after_point` steps takes at most
+/// `2 * word_size + 7` ticks).
+///
+/// ***Space complexity***: `O(word_size * (word_size + bits_after_point))`.
+pub fn divider(bits_before_point: u32, bits_after_point: u32) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::math::divider");
+	combiner.check_collisions();
+
+	let word_size = bits_before_point + bits_after_point;
+	// Amount of bits of the dividend and of the steps
+	let steps = word_size + bits_after_point;
+	// Partial remainder needs one more bit than divisor
+	let rem_size = word_size + 1;
+
+	combiner.add_shapes_cube("in_a", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, -1));
+	combiner.add_shapes_cube("in_b", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 1, -1));
+	combiner.add_shapes_cube("quotient", (steps, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 2, -1));
+
+	let adder_x = rem_size as i32 + 2;
+
+	// -b
+	let negative_b = inverter(rem_size);
+	let (start, size) = negative_b.calculate_bounds();
+	combiner.add("negative_b", negative_b).unwrap();
+	combiner.pos().place_last(Point::new_ng(adder_x, 0, -1 - *size.z() as i32) - start);
+	combiner.connect("in_b", "negative_b");
+
+	let step_adder = adder(rem_size);
+	let (start, size) = step_adder.calculate_bounds();
+	let step_depth = *size.z() as i32;
+
+	for step in 0..steps {
+		// Bit of the dividend, which is appended on this step
+		let bit = steps - 1 - step;
+		let z = step as i32 * step_depth;
+
+		let value = format!("value_{}", step);
+		let sub = format!("sub_{}", step);
+		let take = format!("take_{}", step);
+		let keep = format!("keep_{}", step);
+		let carry = format!("carry_{}", step);
+		let no_carry = format!("no_carry_{}", step);
+		let rem = format!("rem_{}", step);
+
+		combiner.add_shapes_cube(&value, (rem_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, 0, z));
+		combiner.add_shapes_cube(&take, (rem_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, 1, z));
+		combiner.add_shapes_cube(&keep, (rem_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, 2, z));
+		combiner.add_shapes_cube(&rem, (rem_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, 3, z));
+		combiner.add(&carry, OR).unwrap();
+		combiner.pos().place_last((rem_size as i32, 1, z));
+		combiner.add(&no_carry, NOR).unwrap();
+		combiner.pos().place_last((rem_size as i32, 2, z));
+		combiner.add(&sub, step_adder.clone()).unwrap();
+		combiner.pos().place_last(Point::new_ng(adder_x, 0, z) - start);
+
+		// value = (previous remainder << 1) | bit of the dividend
+		if bit >= bits_after_point {
+			combiner.connect(format!("in_a/_/{}_0_0", bit - bits_after_point), format!("{}/_/0_0_0", value));
+		}
+		if step > 0 {
+			combiner.custom(format!("rem_{}", step - 1), &value, shift_connection((1, 0, 0)));
+		}
+
+		combiner.connect(&value, format!("{}/a", sub));
+		combiner.connect("negative_b", format!("{}/b", sub));
+
+		// Carry means, that the value is not less than b. Carry output of
+		// the adder consists of several gates, so it is gathered into one.
+		combiner.connect(format!("{}/carry", sub), &carry);
+		combiner.connect(&carry, &no_carry);
+		combiner.connect(&carry, format!("quotient/_/{}_0_0", bit));
+		combiner.connect(&sub, &take);
+		combiner.dim(&carry, &take, (true, true, true));
+		combiner.connect(&value, &keep);
+		combiner.dim(&no_carry, &keep, (true, true, true));
+
+		combiner.connect(&take, &rem);
+		combiner.connect(&keep, &rem);
+	}
+
+	let mut a = Bind::new("a", "binary", (word_size, 1, 1));
+	a.connect_full("in_a");
+	a.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(a).unwrap();
+	combiner.bind_input(make_rational_bind("a_rational", "in_a", bits_before_point, bits_after_point, bits_after_point, 0)).unwrap();
+
+	let mut b = Bind::new("b", "binary", (word_size, 1, 1));
+	b.connect_full("in_b");
+	b.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(b).unwrap();
+	combiner.bind_input(make_rational_bind("b_rational", "in_b", bits_before_point, bits_after_point, bits_after_point, 0)).unwrap();
+
+	let mut quotient = Bind::new("_", "binary", (word_size, 1, 1));
+	quotient.connect_full("quotient");
+	quotient.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(quotient).unwrap();
+	combiner.bind_output(make_rational_bind("rational", "quotient", bits_before_point, bits_after_point, bits_after_point, 0)).unwrap();
+
+	let mut remainder = Bind::new("remainder", "binary", (word_size, 1, 1));
+	remainder.connect_full(format!("rem_{}", steps - 1));
+	remainder.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(remainder).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+#[test]
+fn barrel_shifter_test() {
+	use crate::testing::{exhaustive_inputs, from_bits, Simulator};
+
+	let mut left = Simulator::new(&barrel_shifter(8, ShiftDir::Left));
+	let mut right = Simulator::new(&barrel_shifter(8, ShiftDir::Right));
+
+	for input in exhaustive_inputs(11) {
+		let (data, amount) = input.split_at(8);
+		let (number, shift) = (from_bits(data), from_bits(amount));
+
+		for sim in [&mut left, &mut right] {
+			sim.set_input("data", data).unwrap();
+			sim.set_input("amount", amount).unwrap();
+			sim.run(7);
+		}
+
+		assert_eq!(left.output_number("_").unwrap(), (number << shift) & 0xff, "{} << {}", number, shift);
+		assert_eq!(right.output_number("_").unwrap(), number >> shift, "{} >> {}", number, shift);
+	}
+
+	// Shift by more than word size gives zero
+	let mut sim = Simulator::new(&barrel_shifter(6, ShiftDir::Left));
+	sim.set_input_number("data", 0b111111).unwrap();
+	sim.set_input_number("amount", 7).unwrap();
+	sim.run(7);
+	assert_eq!(sim.output_number("_").unwrap(), 0);
+}
+
+#[test]
+fn normalize_test() {
+	use crate::testing::Simulator;
+
+	let scheme = normalize(8);
+	scheme.clone().to_json();
+	let mut sim = Simulator::new(&scheme);
+
+	for number in (0..256u64).rev() {
+		sim.set_input_number("_", number).unwrap();
+		sim.run(14);
+
+		let shift = if number == 0 { 0 } else { number.leading_zeros() as u64 - 56 };
+		assert_eq!(sim.output_number("shift").unwrap(), shift, "{}", number);
+		assert_eq!(sim.output_number("mantissa").unwrap(), (number << shift) & 0xff, "{}", number);
+	}
+
+	// Used to overflow connections of the leading bit search
+	normalize(256);
+}
+
+#[test]
+fn divider_test() {
+	use crate::testing::Simulator;
+
+	let mut sim = Simulator::new(&divider(8, 0));
+
+	for (a, b) in [(100, 7), (255, 16), (6, 7), (7, 7), (200, 1), (0, 3), (143, 11)] {
+		sim.set_input_number("a", a).unwrap();
+		sim.set_input_number("b", b).unwrap();
+		sim.run(200);
+
+		assert_eq!(sim.output_number("_").unwrap(), a / b, "{} / {}", a, b);
+		assert_eq!(sim.output_number("remainder").unwrap(), a % b, "{} % {}", a, b);
+	}
+
+	// 4.0 / 1.5 = 2.625 (with 2 fractional bits - 2.5)
+	let mut sim = Simulator::new(&divider(4, 2));
+	sim.set_input_number("a", 0b10000).unwrap();
+	sim.set_input_number("b", 0b00110).unwrap();
+	sim.run(200);
+	assert_eq!(sim.output_number("_").unwrap(), 0b1010);
+	assert_eq!(sim.output_number("rational/integer").unwrap(), 2);
+	// 4.0 - 2.5 * 1.5 = 0.25, with 4 fractional bits
+	assert_eq!(sim.output_number("remainder").unwrap(), 0b100);
+}
+
+#[test]
+fn min_max_test() {
+	use crate::testing::{exhaustive_inputs, from_bits, Simulator};
+
+	let mut sim = Simulator::new(&min_max(3));
+
+	for input in exhaustive_inputs(6) {
+		let (a, b) = input.split_at(3);
+		sim.set_input("a", a).unwrap();
+		sim.set_input("b", b).unwrap();
+		sim.run(12);
+
+		let (a, b) = (from_bits(a), from_bits(b));
+		assert_eq!(sim.output_number("min").unwrap(), a.min(b), "min({}, {})", a, b);
+		assert_eq!(sim.output_number("max").unwrap(), a.max(b), "max({}, {})", a, b);
+	}
+
+	let mut sim = Simulator::new(&min_max(8));
+	sim.set_input_number("a", 200).unwrap();
+	sim.set_input_number("b", 13).unwrap();
+	sim.run(8);
+	assert_ne!(sim.output_number("max").unwrap(), 200);
+	sim.run(1);
+	assert_eq!(sim.output_number("min").unwrap(), 13);
+	assert_eq!(sim.output_number("max").unwrap(), 200);
+}
+
+#[test]
+fn bitwise_test() {
+	use crate::testing::{exhaustive_inputs, from_bits, Simulator};
+
+	let mut sim = Simulator::new(&bitwise(3));
+
+	for input in exhaustive_inputs(6) {
+		let (a, b) = input.split_at(3);
+		sim.set_input("a", a).unwrap();
+		sim.set_input("b", b).unwrap();
+		sim.run(2);
+
+		let (a, b) = (from_bits(a), from_bits(b));
+		assert_eq!(sim.output_number("and").unwrap(), a & b);
+		assert_eq!(sim.output_number("or").unwrap(), a | b);
+		assert_eq!(sim.output_number("xor").unwrap(), a ^ b);
+		assert_eq!(sim.output_number("not_a").unwrap(), !a & 0b111);
+		assert_eq!(sim.output("xor/1").unwrap(), vec![(a ^ b) & 0b10 != 0]);
+	}
+}
+
+#[test]
+fn negate_abs_test() {
+	use crate::testing::Simulator;
+
+	let word_size = 4;
+	let mask = (1u64 << word_size) - 1;
+	let mut negate = Simulator::new(&negate(word_size));
+	let mut abs = Simulator::new(&abs(word_size));
+
+	for number in 0..=mask {
+		negate.set_input_number("_", number).unwrap();
+		abs.set_input_number("_", number).unwrap();
+		negate.run(word_size + 2);
+		abs.run(word_size + 5);
+
+		let negated = number.wrapping_neg() & mask;
+		let expected_abs = if number >> (word_size - 1) == 1 { negated } else { number };
+		assert_eq!(negate.output_number("_").unwrap(), negated, "-{}", number);
+		assert_eq!(abs.output_number("_").unwrap(), expected_abs, "abs({})", number);
+	}
+}
+
+#[test]
+fn lfsr_test() {
+	use crate::testing::Simulator;
+
+	let pulse = |sim: &mut Simulator, input: &str| {
+		sim.set_input(input, &[true]).unwrap();
+		sim.run(1);
+		sim.set_input(input, &[false]).unwrap();
+		sim.run(3);
+	};
+
+	for (word_size, taps) in [(4, vec![3, 2]), (8, vec![7, 5, 4, 3])] {
+		let mask = (1u64 << word_size) - 1;
+		let mut sim = Simulator::new(&lfsr(word_size, &taps));
+
+		sim.set_input_number("seed", 1).unwrap();
+		pulse(&mut sim, "load");
+		sim.set_input_number("seed", 0).unwrap();
+		assert_eq!(sim.output_number("_").unwrap(), 1);
+
+		let mut expected = 1u64;
+		let mut seen = std::collections::HashSet::new();
+		for _ in 0..mask {
+			assert!(seen.insert(expected), "{} repeated", expected);
+
+			let feedback = taps.iter().fold(0, |acc, tap| acc ^ (expected >> tap) & 1);
+			expected = ((expected << 1) | feedback) & mask;
+
+			pulse(&mut sim, "clock");
+			assert_eq!(sim.output_number("_").unwrap(), expected);
+		}
+
+		// Maximal length: back to the seed
+		assert_eq!(expected, 1);
+	}
+}
+
+#[test]
+fn counter_test() {
+	use crate::testing::Simulator;
+
+	let mut sim = Simulator::new(&counter(3));
+	sim.run(5);
+
+	let mut pulse = |input: &str| {
+		sim.set_input(input, &[true]).unwrap();
+		sim.run(1);
+		sim.set_input(input, &[false]).unwrap();
+		sim.run(3);
+		sim.output_number("_").unwrap()
+	};
+
+	assert_eq!(pulse("up"), 1);
+	assert_eq!(pulse("up"), 2);
+	assert_eq!(pulse("up"), 3);
+	assert_eq!(pulse("down"), 2);
+	assert_eq!(pulse("down"), 1);
+	assert_eq!(pulse("down"), 0);
+	// Wraps around
+	assert_eq!(pulse("down"), 7);
+	assert_eq!(pulse("up"), 0);
+
+	for expected in 1..=8 {
+		assert_eq!(pulse("up"), expected % 8);
+	}
+}
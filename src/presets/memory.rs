@@ -1,10 +1,11 @@
 use crate::bind::Bind;
 use crate::combiner::Combiner;
 use crate::positioner::ManualPos;
-use crate::presets::{binary_selector_compact, Scheme};
-use crate::shape::vanilla::{BlockBody, BlockType};
+use crate::presets::{binary_selector, binary_selector_compact, get_bit, Scheme};
+use crate::presets::math::counter;
+use crate::shape::vanilla::{BlockBody, BlockType, Timer};
 use crate::shape::vanilla::GateMode::*;
-use crate::util::{Facing, MAX_CONNECTIONS, Point};
+use crate::util::{Bounds, Facing, MAX_CONNECTIONS, Point, Rot};
 
 /// ***Inputs***: data, write.
 ///
@@ -127,7 +128,7 @@ pub fn raw_memory_block(word_size: u32, size: (u32, u32, u32), make_direct_input
 	let mut combiner = Combiner::pos_manual();
 	let cell = smallest_rw_cell(word_size);
 
-	let cells_count = size.0 * size.1 * size.2;
+	let cells_count = Bounds::from_tuple(size).volume() as u32;
 	let address_size = (cells_count as f64).log2().ceil() as u32;
 
 	// Add all memory cells to the combiner
@@ -244,7 +245,7 @@ pub fn array(word_size: u32, size: (u32, u32, u32), make_direct_inputs: bool, ma
 
 	combiner.add("mem", raw_memory_block(word_size, size, make_direct_inputs, make_direct_outputs)).unwrap();
 
-	let cells_count = size.0 * size.1 * size.2;
+	let cells_count = Bounds::from_tuple(size).volume() as u32;
 	let address_size = (cells_count as f64).log2().ceil() as u32;
 
 	if make_direct_outputs || make_direct_inputs {
@@ -305,6 +306,86 @@ pub fn array(word_size: u32, size: (u32, u32, u32), make_direct_inputs: bool, ma
 	scheme
 }
 
+/// ***Inputs***: addr_a, addr_b, write, apply.
+///
+/// ***Outputs***: read_a, read_b.
+
+///
+/// `array` with two independent read ports. Amount of memory cells is
+/// product of each coordinate of `size` argument.
+///
+/// Data of the cell selected by 'addr_a' is available on 'read_a'
+/// output, data of the cell selected by 'addr_b' - on 'read_b' output.
+/// Both ports have the same delay as reading of `array` has, and allow
+/// for 1-tick threaded reading.
+///
+/// Writes still use a single port: 'addr_a' selects the cell to write
+/// to, and 'write' with 'apply' work exactly as in `array`.
+pub fn array_dual_read(word_size: u32, size: (u32, u32, u32)) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.check_collisions();
+
+	let cells_count = Bounds::from_tuple(size).volume() as u32;
+	let address_size = (cells_count as f64).log2().ceil() as u32;
+
+	// Port A is just a usual array, direct outputs of which lead to port B
+	let port_a = array(word_size, size, false, true);
+	let port_b_x = *port_a.bounds().x() as i32 + 1;
+	combiner.add("port_a", port_a).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+
+	let selector = binary_selector_compact(address_size);
+	let cells_y = *selector.bounds().y() as i32 + 2;
+	combiner.add("select_b", selector).unwrap();
+	combiner.pos().place_last((port_b_x, 2, 0));
+
+	combiner.add_shapes_cube("addr_b", (address_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((port_b_x, 0, 0));
+	combiner.connect("addr_b", "select_b");
+
+	// One more gate to keep delay of both ports equal
+	combiner.add_shapes_cube("read_b", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((port_b_x, 0, 1));
+	let mut read_b = Bind::new("read_b", "_", (word_size, 1, 1));
+	read_b.gen_point_sectors("_", |x, _, _| x.to_string()).unwrap();
+	read_b.connect_full("read_b");
+
+	let mut bus_name = String::new();
+	for i in 0..cells_count {
+		let line_name = format!("line_b_{}", i);
+		let cell_name = format!("cell_b_{}", i);
+
+		combiner.add(&line_name, AND).unwrap();
+		combiner.pos().place_last((port_b_x, cells_y + i as i32, 0));
+		combiner.add_shapes_cube(&cell_name, (word_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((port_b_x + 1, cells_y + i as i32, 0));
+
+		if i % MAX_CONNECTIONS == 0 {
+			// If new bus branch is needed, add it
+			let branch_id = i / MAX_CONNECTIONS;
+			bus_name = format!("read_b_{}", branch_id);
+			combiner.add_shapes_cube(&bus_name, (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+			combiner.pos().place_last((port_b_x, 1, branch_id as i32));
+			combiner.connect(&bus_name, "read_b");
+		}
+
+		combiner.connect(format!("select_b/{}", i), &line_name);
+		combiner.dim(&line_name, &cell_name, (true, true, true));
+		combiner.connect(format!("port_a/{}", i), &cell_name);
+		combiner.connect(&cell_name, &bus_name);
+	}
+
+	combiner.pass_input("addr_a", "port_a/address", Some("binary")).unwrap();
+	combiner.pass_input("addr_b", "addr_b", Some("binary")).unwrap();
+	combiner.pass_input("write", "port_a/write", Some("_")).unwrap();
+	combiner.pass_input("apply", "port_a/apply", Some("logic")).unwrap();
+	combiner.pass_output("read_a", "port_a", Some("_")).unwrap();
+	combiner.bind_output(read_b).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
 /// ***Inputs***: activate, data, xor_gates.
 ///
 /// ***Outputs***: _ (data), xor_gates.
@@ -357,6 +438,164 @@ pub fn smallest_rw_cell(word_size: u32) -> Scheme {
 	scheme
 }
 
+/// ***Inputs***: activate, write, data, xor_gates.
+///
+/// ***Outputs***: _ (data), xor_gates.
+
+///
+/// Same as `smallest_rw_cell`, but can be written directly. Its purpose
+/// is to be used in `fast_array`.
+///
+/// If cell is not activated it won't send data to output and can not
+/// be written. When activated, memory content is available on default
+/// output. To write, send 1-tick (not multiple ticks long) 'write'
+/// signal together with the data to 'data' input. The cell compares
+/// the data with its content by itself, so the data is in memory
+/// 3 ticks later.
+///
+/// Costs `word_size + 1` more gates than `smallest_rw_cell`
+/// (`4 * word_size + 2` gates in total).
+///
+/// 'xor_gates' output leads right to memory gates.
+pub fn fast_rw_cell(word_size: u32) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+
+	combiner.add_shapes_cube("compare", (word_size, 1, 1), XOR, Facing::NegY.to_rot()).unwrap();
+	combiner.add_shapes_cube("pass_data", (word_size, 1, 1), AND, Facing::NegY.to_rot()).unwrap();
+	combiner.add_shapes_cube("memory", (word_size, 1, 1), XOR, Facing::NegY.to_rot()).unwrap();
+	combiner.add_shapes_cube("output", (word_size, 1, 1), AND, Facing::NegY.to_rot()).unwrap();
+	combiner.add("activate", AND).unwrap();
+	combiner.add("write", AND).unwrap();
+
+	combiner.connect("compare", "pass_data");
+	combiner.connect("pass_data", "memory");
+	combiner.connect_iter(["memory"], ["compare", "output", "memory"]);
+	combiner.connect("activate", "write");
+	combiner.dim("activate", "output", (true, true, true));
+	combiner.dim("write", "pass_data", (true, true, true));
+
+	combiner.pass_input("activate", "activate", Some("logic")).unwrap();
+	combiner.pass_input("write", "write", Some("logic")).unwrap();
+	combiner.pass_input("data", "compare", Some("_")).unwrap();
+	combiner.pass_output("_", "output", Some("_")).unwrap();
+
+	combiner.pass_input("xor_gates", "memory", Some("_")).unwrap();
+	combiner.pass_output("xor_gates", "memory", Some("_")).unwrap();
+
+	combiner.pos().place_iter([
+		("activate", (1, 0, word_size as i32)),
+		("write", (2, 0, word_size as i32)),
+		("compare", (0, 0, 0)),
+		("pass_data", (1, 0, 0)),
+		("memory", (2, 0, 0)),
+		("output", (3, 0, 0)),
+	]);
+
+	combiner.pos().rotate_iter([
+		("activate", Facing::NegY.to_rot()),
+		("write", Facing::NegY.to_rot()),
+		("compare", Rot::new(0, -1, 0)),
+		("pass_data", Rot::new(0, -1, 0)),
+		("memory", Rot::new(0, -1, 0)),
+		("output", Rot::new(0, -1, 0)),
+	]);
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: address, write, apply.
+///
+/// ***Outputs***: _ (read).
+
+///
+/// Version of `array` made of `fast_rw_cell`s, which allows for fast
+/// writing. Amount of memory cells is product of each coordinate of
+/// `size` argument.
+///
+/// Reading works just like in `array`, but with 4 ticks delay.
+///
+/// Writing does not need the selected cell to be read first: set
+/// 'address' at least 1 tick before 'apply', then send 1-tick (not
+/// multiple ticks long) signal to 'apply' together with the data on
+/// 'write' input. Address and data only need to be kept during the
+/// 'apply' tick, and the written data can be read 5 ticks after it.
+///
+/// Tradeoff: each cell has its own comparator, so the array costs
+/// `cells_count * (word_size + 1)` more gates than `array` of the same
+/// size.
+///
+/// Also: will cause connections overflow if there is more than 65 025
+/// memory cells (`MAX_CONNECTIONS.pow(2)`).
+pub fn fast_array(word_size: u32, size: (u32, u32, u32)) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.check_collisions();
+
+	let cells_count = Bounds::from_tuple(size).volume() as u32;
+	let address_size = (cells_count as f64).log2().ceil() as u32;
+
+	let all_cells: Vec<String> = add_cells(&mut combiner, fast_rw_cell(word_size), size)
+		.into_iter().map(|(cell, _pos)| cell).collect();
+
+	combiner.add("address", binary_selector_compact(address_size)).unwrap();
+	combiner.pos().place_last((0, -2, 0));
+	combiner.pos().rotate_last((1, 0, 1));
+
+	let mut address = Bind::new("address", "binary", (address_size, 1, 1));
+	address.gen_point_sectors("bit", |x, _, _| x.to_string()).unwrap();
+	address.connect_full("address");
+
+	let mut write = Bind::new("write", "_", (word_size, 1, 1));
+	let mut read = Bind::new("_", "_", (word_size, 1, 1));
+	let mut apply = Bind::new("apply", "logic", (1, 1, 1));
+	write.gen_point_sectors("_", |x, _, _| x.to_string()).unwrap();
+	read.gen_point_sectors("_", |x, _, _| x.to_string()).unwrap();
+
+	let mut read_name = "read_none".to_string();
+	let mut write_name = "write_none".to_string();
+	let mut apply_name = "apply_none".to_string();
+
+	for (i, cell) in all_cells.iter().enumerate() {
+		let bus_branch_id = (i as u32) / MAX_CONNECTIONS;
+
+		if (i as u32) % MAX_CONNECTIONS == 0 {
+			// If new bus branch is needed, add it
+			read_name = format!("read_{}", bus_branch_id);
+			write_name = format!("write_{}", bus_branch_id);
+			apply_name = format!("apply_{}", bus_branch_id);
+			combiner.add_shapes_cube(&read_name, (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+			combiner.add_shapes_cube(&write_name, (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+			combiner.add(&apply_name, OR).unwrap();
+			read.connect_full(&read_name);
+			write.connect_full(&write_name);
+			apply.connect_full(&apply_name);
+
+			let y = (bus_branch_id as i32) * 3;
+			combiner.pos().place_iter([
+				(&read_name, (-1, y, 0)),
+				(&write_name, (-1, y + 1, 0)),
+				(&apply_name, (-1, y + 2, 0)),
+			]);
+			combiner.pos().rotate(&read_name, (0, -1, 0));
+			combiner.pos().rotate(&write_name, (0, -1, 0));
+			combiner.pos().rotate(&apply_name, Facing::NegX.to_rot());
+		}
+
+		combiner.connect(format!("address/{}", i), format!("{}/activate", cell));
+		combiner.connect(cell, &read_name);
+		combiner.connect(&write_name, format!("{}/data", cell));
+		combiner.connect(&apply_name, format!("{}/write", cell));
+	}
+
+	combiner.bind_input(address).unwrap();
+	combiner.bind_input(write).unwrap();
+	combiner.bind_input(apply).unwrap();
+	combiner.bind_output(read).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
 /// ***Inputs***: data, write.
 ///
 /// ***Outputs***: first, last, 0, 1, 2, 3 etc...
@@ -499,26 +738,609 @@ pub fn bidirectional_shift_array(word_size: u32, size: (u32, u32, u32)) -> Schem
 	scheme
 }
 
-fn add_cells(combiner: &mut Combiner<ManualPos>, cell: Scheme, size: (u32, u32, u32)) -> Vec<(String, Point)> {
-	let cell_size: (i32, i32, i32) = cell.bounds().cast().tuple();
-	let mut all_cells: Vec<(String, Point)> = vec![];
-
-	for x in 0..size.0 {
-		for y in 0..size.1 {
-			for z in 0..size.2 {
-				let name = format!("{}_{}_{}", x, y, z);
-				combiner.add(name.clone(), cell.clone()).unwrap();
-				let pos: Point = (
-					x as i32 * cell_size.0,
-					y as i32 * cell_size.1,
-					z as i32 * cell_size.2
-				).into();
-				combiner.pos().place_last(pos);
-
-				all_cells.push((name, pos));
+/// ***Inputs***: push, pop, data_in.
+///
+/// ***Outputs***: data_out, full, empty.
+
+///
+/// First-in-first-out queue of `depth` words.
+///
+/// To push a word send it to 'data_in' and 1-tick signal to 'push'
+/// simultaneously. To remove the oldest word send 1-tick signal to
+/// 'pop'. The oldest word is always available on 'data_out' ('data_out'
+/// is zero, if the queue is empty).
+///
+/// Push and pop can be sent in the same tick: the oldest word is
+/// removed and the new one is added. Push into full queue is ignored
+/// (unless it goes together with pop), pop from empty queue is ignored
+/// too.
+///
+/// Words are stored in `shift_array`, which shifts on every push. The
+/// amount of stored words is kept in `bidirectional_shift_array` of
+/// 1-bit cells as a line of ones (push shifts in one more, pop shifts
+/// one out), and the last one selects the oldest word.
+///
+/// Timing: operations must be at least 8 ticks apart. 'full' and
+/// 'empty' are updated 8 ticks after the operation, 'data_out' - 10
+/// ticks after it.
+///
+/// Will panic if `depth` is zero or bigger than [`MAX_CONNECTIONS`].
+pub fn fifo(word_size: u32, depth: u32) -> Scheme {
+	if depth == 0 || depth > MAX_CONNECTIONS {
+		panic!("FIFO depth must be in range 1..={} (got {})", MAX_CONNECTIONS, depth);
+	}
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::memory::fifo");
+	combiner.check_collisions();
+
+	// Storage and occupancy arrays are stacked along Z axis
+	let data = shift_array(word_size, (depth, 1, 1));
+	let occupancy = bidirectional_shift_array(1, (depth, 1, 1));
+	let (data_start, data_size) = data.calculate_bounds();
+	let (occupancy_start, occupancy_size) = occupancy.calculate_bounds();
+
+	let occupancy_z = *data_size.z() as i32 + 1;
+	let z = occupancy_z + *occupancy_size.z() as i32 + 1;
+
+	combiner.add("data", data).unwrap();
+	combiner.pos().place_last(Point::new_ng(0, 0, 0) - data_start);
+	combiner.add("occupancy", occupancy).unwrap();
+	combiner.pos().place_last(Point::new_ng(0, 0, occupancy_z) - occupancy_start);
+
+	// Control logic. Pushes and pops are aligned, so that both arrays
+	// receive their write signals 4 ticks after the inputs.
+	let control = [
+		("push_g", OR), ("push_g2", OR), ("can_push", OR), ("push_ok", AND), ("push_ok_n", NAND),
+		("pop_g", OR), ("pop_g2", OR), ("pop_ok", AND), ("pop_ok_n", NAND),
+		("fwd", AND), ("rev", AND), ("data_write", OR),
+		("full", OR), ("not_full", NOR), ("empty", NOR),
+	];
+	for (i, (name, mode)) in control.into_iter().enumerate() {
+		combiner.add(name, mode).unwrap();
+		combiner.pos().place_last((i as i32, 1, z));
+	}
+
+	let last = format!("occupancy/{}", depth - 1);
+	combiner.connect_iter([last.as_str()], ["full", "not_full"]);
+	combiner.connect("occupancy/0", "empty");
+
+	combiner.connect("push_g", "push_g2");
+	combiner.connect_iter(["pop_g", "not_full"], ["can_push"]);
+	combiner.connect_iter(["push_g2", "can_push"], ["push_ok", "push_ok_n"]);
+
+	combiner.connect("pop_g", "pop_g2");
+	combiner.connect_iter(["pop_g2", "occupancy/0"], ["pop_ok", "pop_ok_n"]);
+
+	combiner.connect_iter(["push_ok", "pop_ok_n"], ["fwd"]);
+	combiner.connect_iter(["pop_ok", "push_ok_n"], ["rev"]);
+	combiner.connect("push_ok", "data_write");
+
+	combiner.connect_iter(["fwd"], ["occupancy/write_fwd", "occupancy/data_fwd"]);
+	combiner.connect("rev", "occupancy/write_rev");
+	combiner.connect("data_write", "data/write");
+
+	// Data waits for the write signal (timers are 2 blocks long)
+	combiner.add_shapes_cube("data_delay", (word_size, 1, 1), Timer::new(3), Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, z));
+	combiner.connect("data_delay", "data/data");
+
+	// The oldest word is in the last occupied cell
+	combiner.add_shapes_cube("top", (depth, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, z + 2));
+	if depth > 1 {
+		combiner.add_shapes_cube("top_n", (depth - 1, 1, 1), NOR, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, 1, z + 2));
+	}
+
+	combiner.add_shapes_cube("data_out", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, z + 3));
+
+	for i in 0..depth {
+		let top = format!("top/_/{}_0_0", i);
+		let select = format!("select_{}", i);
+
+		combiner.connect(format!("occupancy/{}", i), &top);
+		if i + 1 < depth {
+			let top_n = format!("top_n/_/{}_0_0", i);
+			combiner.connect(format!("occupancy/{}", i + 1), &top_n);
+			combiner.connect(&top_n, &top);
+		}
+
+		combiner.add_shapes_cube(&select, (word_size, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((0, 0, z + 4 + i as i32));
+		combiner.dim(&top, &select, (true, true, true));
+		combiner.connect(format!("data/{}", i), &select);
+		combiner.connect(&select, "data_out");
+	}
+
+	combiner.pass_input("push", "push_g", Some("logic")).unwrap();
+	combiner.pass_input("pop", "pop_g", Some("logic")).unwrap();
+	combiner.pass_input("data_in", "data_delay", Some("binary")).unwrap();
+	combiner.pass_output("data_out", "data_out", Some("binary")).unwrap();
+	combiner.pass_output("full", "full", Some("logic")).unwrap();
+	combiner.pass_output("empty", "empty", Some("logic")).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: push, pop, data_in.
+///
+/// ***Outputs***: top, full, empty.
+
+///
+/// Last-in-first-out stack of `depth` words.
+///
+/// To push a word send it to 'data_in' and 1-tick signal to 'push'
+/// simultaneously. To remove the last pushed word send 1-tick signal
+/// to 'pop'. The last pushed word is always available on 'top' ('top'
+/// is zero, if the stack is empty).
+///
+/// Push into full stack and pop from empty stack are ignored. Push
+/// and pop sent in the same tick are ignored too.
+///
+/// Words are stored in `array` of `depth + 1` cells, which is addressed
+/// by up/down `counter` - the stack pointer. Stack pointer is the
+/// amount of stored words, so it points at the top word (words are
+/// stored from cell 1, cell 0 is never written and reads as zero).
+///
+/// Since `array` can only write into the cell, which is already
+/// selected, operations take several steps:
+/// - push: stack pointer is incremented, the array selects the new
+///   cell, and only then the delayed word is written into it. For a
+///   few ticks before the write 'top' shows the old content of the new
+///   cell.
+/// - pop: stack pointer is decremented, and the array reads the word
+///   below. Popped word is not erased, the next push overwrites it.
+///
+/// Timing: operations must be at least 21 ticks apart. Stack pointer
+/// is updated 6 ticks after the operation, 'full' and 'empty' - 7
+/// ticks after it, 'top' - 20 ticks after push and 12 ticks after
+/// pop.
+///
+/// Will panic if `depth` is zero.
+pub fn stack(word_size: u32, depth: u32) -> Scheme {
+	if depth == 0 {
+		panic!("Stack depth must be at least 1");
+	}
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::memory::stack");
+	combiner.check_collisions();
+
+	let pointer_size = ((depth + 1) as f64).log2().ceil() as u32;
+
+	// Storage and stack pointer are stacked along Z axis
+	let mem = array(word_size, (depth + 1, 1, 1), false, false);
+	let pointer = counter(pointer_size);
+	let (mem_start, mem_size) = mem.calculate_bounds();
+	let (pointer_start, pointer_size_3d) = pointer.calculate_bounds();
+
+	let pointer_z = *mem_size.z() as i32 + 1;
+	let z = pointer_z + *pointer_size_3d.z() as i32 + 1;
+
+	combiner.add("mem", mem).unwrap();
+	combiner.pos().place_last(Point::new_ng(0, 0, 0) - mem_start);
+	combiner.add("pointer", pointer).unwrap();
+	combiner.pos().place_last(Point::new_ng(0, 0, pointer_z) - pointer_start);
+	combiner.connect("pointer", "mem/address");
+
+	// Control logic
+	let control = [
+		("push_g", OR), ("push_g2", OR), ("push_n", NOR), ("push_ok", AND),
+		("pop_g", OR), ("pop_g2", OR), ("pop_n", NOR), ("pop_ok", AND),
+		("full", AND), ("not_full", NAND), ("empty", NOR), ("not_empty", OR),
+	];
+	for (i, (name, mode)) in control.into_iter().enumerate() {
+		combiner.add(name, mode).unwrap();
+		combiner.pos().place_last((i as i32, 1, z));
+	}
+
+	// Stack is full, if the pointer equals to `depth`
+	combiner.add_shapes_cube("pointer_n", (pointer_size, 1, 1), NOR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 2, z));
+	combiner.connect("pointer", "pointer_n");
+	for bit in 0..pointer_size {
+		let from = match get_bit(depth as i64, bit) {
+			true => format!("pointer/_/{}", bit),
+			false => format!("pointer_n/_/{}_0_0", bit),
+		};
+		combiner.connect_iter([from.as_str()], ["full", "not_full"]);
+	}
+	combiner.dim("pointer", "empty", (true, true, true));
+	combiner.dim("pointer", "not_empty", (true, true, true));
+
+	// Push and pop are delayed by 1 tick to be aligned with 'push_n'
+	// and 'pop_n'
+	combiner.connect_iter(["push_g"], ["push_g2", "push_n"]);
+	combiner.connect_iter(["pop_g"], ["pop_g2", "pop_n"]);
+	combiner.connect("push_g2", "push_ok");
+	combiner.connect("pop_g2", "pop_ok");
+	combiner.connect_iter(["pop_n", "not_full"], ["push_ok"]);
+	combiner.connect_iter(["push_n", "not_empty"], ["pop_ok"]);
+
+	combiner.connect("push_ok", "pointer/up");
+	combiner.connect("pop_ok", "pointer/down");
+
+	// The word and 'apply' signal wait, until the new cell is selected
+	// (timers are 2 blocks long)
+	combiner.add_shapes_cube("data_delay", (word_size, 1, 1), Timer::new(10), Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, z));
+	combiner.add_shapes_cube("apply_delay", (1, 1, 1), Timer::new(7), Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((word_size as i32, 0, z));
+
+	combiner.connect("push_ok", "apply_delay");
+	combiner.connect("apply_delay", "mem/apply");
+	combiner.connect("data_delay", "mem/write");
+
+	combiner.pass_input("push", "push_g", Some("logic")).unwrap();
+	combiner.pass_input("pop", "pop_g", Some("logic")).unwrap();
+	combiner.pass_input("data_in", "data_delay", Some("binary")).unwrap();
+	combiner.pass_output("top", "mem", Some("binary")).unwrap();
+	combiner.pass_output("full", "full", Some("logic")).unwrap();
+	combiner.pass_output("empty", "empty", Some("logic")).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+/// ***Inputs***: address.
+///
+/// ***Outputs***: _ (word).
+
+///
+/// Read-only memory, which contains given `data`. Each entry of `data`
+/// is one word (only lower `word_size` bits are used). Send binary
+/// address to 'address' input and 3 ticks later the word will be
+/// available on default output. Addresses beyond the length of `data`
+/// are read as zero.
+///
+/// Allows for 1-tick threaded reading, just like `array`.
+///
+/// ***Space complexity***: `O(data.len() + word_size)`.
+pub fn rom(word_size: u32, data: &[u64]) -> Scheme {
+	let address_size = ((data.len() as f64).log2().ceil() as u32).max(1);
+
+	let mut combiner = Combiner::pos_manual();
+	// Inputs and outputs of the ROM are used by a lot of gates
+	combiner.auto_split_overflow();
+
+	let selector = binary_selector_compact(address_size);
+	let selector_size: (i32, i32, i32) = selector.bounds().cast().tuple();
+	combiner.add("selector", selector).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+
+	combiner.add_shapes_cube("lines", (1, data.len() as u32, 1), AND, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((selector_size.0, 0, 0));
+
+	combiner.add_shapes_cube("word", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((selector_size.0 + 1, 0, 0));
+
+	for (address, value) in data.iter().enumerate() {
+		let line = format!("lines/_/0_{}_0", address);
+		combiner.connect(format!("selector/{}", address), &line);
+
+		for bit in 0..word_size.min(64) {
+			if get_bit(*value as i64, bit) {
+				combiner.connect(&line, format!("word/_/{}_0_0", bit));
 			}
 		}
 	}
 
-	all_cells
+	combiner.pass_input("address", "selector", Some("binary")).unwrap();
+	combiner.pass_output("_", "word", Some("binary")).unwrap();
+
+	let (scheme, _invalid_conns) = combiner.compile().unwrap();
+	scheme
+}
+
+#[test]
+fn rom_test() {
+	use crate::testing::Simulator;
+
+	let data = [5, 0, 7, 1, 2];
+	let mut sim = Simulator::new(&rom(3, &data));
+
+	for address in 0..8 {
+		sim.set_input_number("address", address).unwrap();
+		sim.run(3);
+
+		let expected = data.get(address as usize).cloned().unwrap_or(0);
+		assert_eq!(sim.output_number("_").unwrap(), expected);
+	}
+
+	// New address every tick, words come 3 ticks later
+	let addresses = [2, 0, 3, 4, 2];
+	for (tick, address) in addresses.iter().chain(&[0, 0]).enumerate() {
+		sim.set_input_number("address", *address).unwrap();
+		sim.step();
+
+		if tick >= 2 {
+			assert_eq!(sim.output_number("_").unwrap(), data[addresses[tick - 2] as usize]);
+		}
+	}
+}
+
+/// ***Inputs***: data, clock, delay.
+///
+/// ***Outputs***: _ (delayed data).
+
+///
+/// Delays words by selectable amount of clock pulses. Each 1-tick
+/// signal to 'clock' stores a word from 'data' input. Default output
+/// shows the word, that was stored 'delay' (binary number, from 0 to
+/// `max_delay`) pulses before the last one.
+///
+/// Internally it is a `shift_array` of `max_delay + 1` cells with a
+/// selector on its outputs.
+pub fn delay_line(word_size: u32, max_delay: u32) -> Scheme {
+	let cells_count = max_delay + 1;
+	let delay_size = ((cells_count as f64).log2().ceil() as u32).max(1);
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.auto_split_overflow();
+
+	combiner.add("array", shift_array(word_size, (cells_count, 1, 1))).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+	let array_size: (i32, i32, i32) = combiner.last_scheme().unwrap().bounds().cast().tuple();
+
+	combiner.add("selector", binary_selector(delay_size)).unwrap();
+	combiner.pos().place_last((0, 0, array_size.2));
+
+	combiner.add_shapes_cube("lines", (cells_count, 1, 1), AND, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, -1, array_size.2));
+
+	combiner.add_shapes_cube("taps", (word_size, cells_count, 1), AND, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, -2 - cells_count as i32, array_size.2));
+
+	combiner.add_shapes_cube("output", (word_size, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, -3 - cells_count as i32, array_size.2));
+
+	for i in 0..cells_count {
+		let line = format!("lines/_/{}_0_0", i);
+		combiner.connect(format!("selector/{}", i), &line);
+
+		for bit in 0..word_size {
+			let tap = format!("taps/_/{}_{}_0", bit, i);
+			combiner.connect(&line, &tap);
+			combiner.connect(format!("array/{}/{}", i, bit), &tap);
+			combiner.connect(&tap, format!("output/_/{}_0_0", bit));
+		}
+	}
+
+	combiner.pass_input("data", "array/data", Some("binary")).unwrap();
+	combiner.pass_input("clock", "array/write", Some("logic")).unwrap();
+	combiner.pass_input("delay", "selector", Some("binary")).unwrap();
+	combiner.pass_output("_", "output", Some("binary")).unwrap();
+
+	let (scheme, _invalid_conns) = combiner.compile().unwrap();
+	scheme
+}
+
+#[test]
+fn delay_line_test() {
+	use crate::testing::Simulator;
+
+	let scheme = delay_line(8, 16);
+	assert_eq!(scheme.inputs().len(), 3);
+	assert_eq!(scheme.output("_").unwrap().0.bounds(), (8, 1, 1).into());
+
+	let mut sim = Simulator::new(&delay_line(4, 3));
+	let words = [3, 9, 14, 6, 1];
+
+	for word in words {
+		sim.set_input_number("data", word).unwrap();
+		sim.set_input_number("clock", 1).unwrap();
+		sim.run(1);
+		sim.set_input_number("clock", 0).unwrap();
+		sim.run(10);
+	}
+
+	for delay in 0..4 {
+		sim.set_input_number("delay", delay).unwrap();
+		sim.run(10);
+		assert_eq!(sim.output_number("_").unwrap(), words[words.len() - 1 - delay as usize]);
+	}
+}
+
+fn add_cells(combiner: &mut Combiner<ManualPos>, cell: Scheme, size: (u32, u32, u32)) -> Vec<(String, Point)> {
+	let cell_size: Point = cell.bounds().cast();
+
+	let names = combiner.add_grid("cell", cell, size).unwrap();
+	let poses = Bounds::from_tuple(size).points()
+		.map(|point| point * cell_size);
+
+	names.into_iter().zip(poses).collect()
+}
+
+#[test]
+fn array_dual_read_test() {
+	use crate::testing::Simulator;
+
+	let scheme = array_dual_read(4, (2, 2, 2));
+	assert_eq!(scheme.inputs().len(), 4);
+	assert_eq!(scheme.outputs().len(), 2);
+
+	let mut sim = Simulator::new(&scheme);
+	let data = [3, 9, 14, 6, 1, 0, 15, 8];
+
+	for (address, word) in data.iter().enumerate() {
+		sim.set_input_number("addr_a", address as u64).unwrap();
+		sim.set_input_number("write", *word).unwrap();
+		sim.run(6);
+		sim.set_input_number("apply", 1).unwrap();
+		sim.run(1);
+		sim.set_input_number("apply", 0).unwrap();
+		sim.run(5);
+	}
+
+	for address_a in 0..data.len() {
+		for address_b in 0..data.len() {
+			sim.set_input_number("addr_a", address_a as u64).unwrap();
+			sim.set_input_number("addr_b", address_b as u64).unwrap();
+			sim.run(6);
+
+			assert_eq!(sim.output_number("read_a").unwrap(), data[address_a]);
+			assert_eq!(sim.output_number("read_b").unwrap(), data[address_b]);
+		}
+	}
+}
+
+#[test]
+fn fifo_test() {
+	use crate::testing::Simulator;
+
+	let mut sim = Simulator::new(&fifo(4, 3));
+	sim.run(10);
+
+	let operate = |sim: &mut Simulator, push: Option<u64>, pop: bool| {
+		if let Some(word) = push {
+			sim.set_input_number("data_in", word).unwrap();
+			sim.set_input("push", &[true]).unwrap();
+		}
+		sim.set_input("pop", &[pop]).unwrap();
+		sim.run(1);
+
+		sim.set_input_number("data_in", 0).unwrap();
+		sim.set_input("push", &[false]).unwrap();
+		sim.set_input("pop", &[false]).unwrap();
+		sim.run(11);
+	};
+	let state = |sim: &Simulator| (
+		sim.output_number("data_out").unwrap(),
+		sim.output("full").unwrap()[0],
+		sim.output("empty").unwrap()[0],
+	);
+
+	assert_eq!(state(&sim), (0, false, true));
+
+	operate(&mut sim, Some(5), false);
+	assert_eq!(state(&sim), (5, false, false));
+	operate(&mut sim, Some(9), false);
+	assert_eq!(state(&sim), (5, false, false));
+	operate(&mut sim, Some(12), false);
+	assert_eq!(state(&sim), (5, true, false));
+
+	// Push into full queue is ignored
+	operate(&mut sim, Some(1), false);
+	assert_eq!(state(&sim), (5, true, false));
+
+	operate(&mut sim, None, true);
+	assert_eq!(state(&sim), (9, false, false));
+
+	// Simultaneous push and pop
+	operate(&mut sim, Some(7), true);
+	assert_eq!(state(&sim), (12, false, false));
+
+	operate(&mut sim, None, true);
+	assert_eq!(state(&sim), (7, false, false));
+	operate(&mut sim, None, true);
+	assert_eq!(state(&sim), (0, false, true));
+
+	// Pop from empty queue is ignored
+	operate(&mut sim, None, true);
+	assert_eq!(state(&sim), (0, false, true));
+	operate(&mut sim, Some(3), true);
+	assert_eq!(state(&sim), (3, false, false));
+}
+
+#[test]
+fn stack_test() {
+	use crate::testing::Simulator;
+
+	let mut sim = Simulator::new(&stack(4, 3));
+	sim.run(10);
+
+	let operate = |sim: &mut Simulator, push: Option<u64>, pop: bool| {
+		if let Some(word) = push {
+			sim.set_input_number("data_in", word).unwrap();
+			sim.set_input("push", &[true]).unwrap();
+		}
+		sim.set_input("pop", &[pop]).unwrap();
+		sim.run(1);
+
+		sim.set_input_number("data_in", 0).unwrap();
+		sim.set_input("push", &[false]).unwrap();
+		sim.set_input("pop", &[false]).unwrap();
+		sim.run(20);
+	};
+	let state = |sim: &Simulator| (
+		sim.output_number("top").unwrap(),
+		sim.output("full").unwrap()[0],
+		sim.output("empty").unwrap()[0],
+	);
+
+	assert_eq!(state(&sim), (0, false, true));
+
+	operate(&mut sim, Some(5), false);
+	assert_eq!(state(&sim), (5, false, false));
+	operate(&mut sim, Some(9), false);
+	assert_eq!(state(&sim), (9, false, false));
+	operate(&mut sim, Some(12), false);
+	assert_eq!(state(&sim), (12, true, false));
+
+	// Push into full stack is ignored
+	operate(&mut sim, Some(1), false);
+	assert_eq!(state(&sim), (12, true, false));
+
+	operate(&mut sim, None, true);
+	assert_eq!(state(&sim), (9, false, false));
+
+	// Simultaneous push and pop are ignored
+	operate(&mut sim, Some(7), true);
+	assert_eq!(state(&sim), (9, false, false));
+
+	operate(&mut sim, None, true);
+	assert_eq!(state(&sim), (5, false, false));
+	operate(&mut sim, None, true);
+	assert_eq!(state(&sim), (0, false, true));
+
+	// Pop from empty stack is ignored
+	operate(&mut sim, None, true);
+	assert_eq!(state(&sim), (0, false, true));
+
+	// Popped words are overwritten
+	operate(&mut sim, Some(3), false);
+	assert_eq!(state(&sim), (3, false, false));
+	operate(&mut sim, Some(10), false);
+	assert_eq!(state(&sim), (10, false, false));
+	operate(&mut sim, None, true);
+	assert_eq!(state(&sim), (3, false, false));
+}
+
+#[test]
+fn fast_array_test() {
+	use crate::testing::Simulator;
+
+	let scheme = fast_array(4, (3, 2, 1));
+	let mut sim = Simulator::new(&scheme);
+	let data = [3, 9, 14, 6, 1, 15];
+
+	for (address, word) in data.iter().enumerate() {
+		sim.set_input_number("address", address as u64).unwrap();
+		sim.run(1);
+		sim.set_input_number("write", *word).unwrap();
+		sim.set_input_number("apply", 1).unwrap();
+		sim.run(1);
+		sim.set_input_number("write", 0).unwrap();
+		sim.set_input_number("apply", 0).unwrap();
+	}
+	sim.run(5);
+
+	for (address, word) in data.iter().enumerate() {
+		sim.set_input_number("address", address as u64).unwrap();
+		sim.run(4);
+		assert_eq!(sim.output_number("_").unwrap(), *word);
+	}
+
+	// Rewriting a cell with the data it already has does not change it
+	sim.set_input_number("address", 2).unwrap();
+	sim.set_input_number("write", 14).unwrap();
+	sim.run(2);
+	sim.set_input_number("apply", 1).unwrap();
+	sim.run(1);
+	sim.set_input_number("apply", 0).unwrap();
+	sim.run(5);
+	assert_eq!(sim.output_number("_").unwrap(), 14);
 }
\ No newline at end of file
@@ -5,12 +5,14 @@ use crate::positioner::{ManualPos, Positioner};
 use crate::scheme::Scheme;
 use crate::shape::Shape;
 use crate::shape::vanilla::GateMode::*;
+use crate::shape::vanilla::Timer;
 use crate::util::{Bounds, Facing, MAX_CONNECTIONS, Point, Rot};
 
 pub mod math;
 pub mod memory;
 pub mod convertors;
 pub mod display;
+pub mod control;
 
 // Basic math:
 // adder - done
@@ -18,9 +20,9 @@ pub mod display;
 // multiplier - done
 // thread adder - done
 // multiplier on thread adder - done
-// divider
+// divider - done
 // fastest counter
-// counter that has +1 and -1
+// counter that has +1 and -1 - done
 // xor memory cleaning module
 
 // Memory:
@@ -39,7 +41,7 @@ pub mod display;
 // Small symbol display
 // Full symbol display
 // Graphics display (matrix symbol)
-// Graphics display from N tables
+// Graphics display from N tables - done
 
 // Misc:
 // Number table generator
@@ -166,6 +168,86 @@ pub fn connect_safe<P, T, N, S>(
 	Ok(activators_count)
 }
 
+/// Same as [`connect_safe`], but activators (nodes) are connected into
+/// balanced tree, so no node exceeds [`MAX_CONNECTIONS`] at any level.
+/// `create_node(combiner, level, index)` must add new buffer gate and
+/// return its name; level 0 nodes are connected to `targets` directly.
+/// Returns name of the root node, which has to be connected to (or
+/// from, if `rev`) the source. Empty `targets` give
+/// [`Error::NoTargets`], since there is nothing to be the root.
+///
+/// Every level of the tree adds 1 tick of delay.
+///
+/// # Example
+/// ```
+/// # use sm_logic::combiner::Combiner;
+/// # use sm_logic::presets::connect_tree;
+/// # use sm_logic::shape::vanilla::GateMode;
+/// let mut combiner = Combiner::pos_manual();
+/// combiner.add_shapes_cube("targets", (600, 1, 1), GateMode::AND, (0, 0, 0)).unwrap();
+/// combiner.pos().place_last((0, 0, 0));
+///
+/// let root = connect_tree(
+/// 	&mut combiner,
+/// 	(0..600).map(|i| format!("targets/_/{}_0_0", i)),
+/// 	|combiner, level, index| {
+/// 		let name = format!("node_{}_{}", level, index);
+/// 		combiner.add(&name, GateMode::OR).unwrap();
+/// 		combiner.pos().place_last((index as i32, 1 + level as i32, 0));
+/// 		name
+/// 	},
+/// 	false
+/// ).unwrap();
+///
+/// // 3 nodes connected to the targets, and a root connected to them
+/// assert_eq!(root, "node_1_0");
+/// assert!(combiner.compile().is_ok());
+/// ```
+pub fn connect_tree<P, T, N, S>(
+	combiner: &mut Combiner<P>,
+	targets: T,
+	mut create_node: N,
+	rev: bool
+) -> Result<String, Error>
+	where T: IntoIterator, <T as IntoIterator>::Item: Into<String>,
+			N: FnMut(&mut Combiner<P>, u32, u32) -> S,
+			S: Into<String>, P: Positioner
+{
+	let mut level_targets: Vec<String> = targets.into_iter().map(|target| target.into()).collect();
+	let mut level = 0;
+
+	if level_targets.is_empty() {
+		return Err(Error::NoTargets {
+			tip: "Tree of connections needs at least one target.".to_string(),
+		});
+	}
+
+	loop {
+		let mut nodes: Vec<String> = vec![];
+
+		for (index, chunk) in level_targets.chunks(MAX_CONNECTIONS as usize).enumerate() {
+			let node: String = create_node(combiner, level, index as u32).into();
+
+			for target in chunk {
+				if !rev {
+					combiner.connect(&node, target);
+				} else {
+					combiner.connect(target, &node);
+				}
+			}
+
+			nodes.push(node);
+		}
+
+		if nodes.len() == 1 {
+			return Ok(nodes.pop().unwrap());
+		}
+
+		level_targets = nodes;
+		level += 1;
+	}
+}
+
 /// ***Inputs***: data, activator.
 ///
 /// ***Outputs***: _ (filter).
@@ -243,21 +325,17 @@ fn input_filter_rational(bits_before_point: u32, bits_after_point: u32) -> Schem
 pub fn binary_selector(word_size: u32) -> Scheme {
 	let mut combiner = Combiner::pos_manual();
 
-	let selector = binary_selector_compact(word_size);
-	let outputs: Vec<String> = selector.outputs().iter().map(|slot| slot.name().clone()).collect();
-
-	combiner.add("selector", selector).unwrap();
+	combiner.add_pass_filtered(
+		"selector",
+		binary_selector_compact(word_size),
+		|_| None,
+		|slot| match slot.name().as_str() {
+			"_" => None,
+			name => Some((name.to_string(), None)),
+		},
+	).unwrap();
 	combiner.pos().place_last((1, 0, 0));
 
-	for output in outputs {
-		if output.eq("_") {
-			continue;
-		}
-
-		let path = format!("selector/{}", output);
-		combiner.pass_output(output, path, None as Option<String>).unwrap();
-	}
-
 	combiner.add_shapes_cube("input", (word_size, 1, 1), OR, Facing::PosY.to_rot()).unwrap();
 	combiner.pass_input("_", "input", Some("binary")).unwrap();
 	combiner.pos().place_last((0, 0, 0));
@@ -335,24 +413,15 @@ pub fn shapes_cube_combiner<B, S, R>(bounds: B, from_shape: S, shape_rot: R) ->
 	let shape_rot = shape_rot.into();
 	shape.rotate(shape_rot.clone());
 
-	let bounds: (i32, i32, i32) = bounds.into().cast().tuple();
+	let bounds: Bounds = bounds.into();
 	let mut combiner = Combiner::pos_manual();
-	let mut slot = Bind::new("_", "_", (bounds.0 as u32, bounds.1 as u32, bounds.2 as u32));
-
-	for x in 0..bounds.0 {
-		for y in 0..bounds.1 {
-			for z in 0..bounds.2 {
-				let name = format!("{}_{}_{}", x, y, z);
-
-				combiner.add(&name, shape.clone()).unwrap();
+	let mut slot = Bind::new("_", "_", bounds);
 
-				let pos = Point::new(x, y, z);
-				combiner.pos().place_last(pos * shape.bounds().cast());
-
-				slot.connect(((x, y, z), (1, 1, 1)), &name);
-				slot.add_sector(name, (x, y, z), (1, 1, 1), "logic").unwrap();
-			}
-		}
+	let names = combiner.add_grid("shape", shape, bounds.tuple()).unwrap();
+	for (name, point) in names.into_iter().zip(bounds.points()) {
+		let (x, y, z) = point.tuple();
+		slot.connect(((x, y, z), (1, 1, 1)), &name);
+		slot.add_sector(format!("{}_{}_{}", x, y, z), (x, y, z), (1, 1, 1), "logic").unwrap();
 	}
 
 	if has_input {
@@ -371,6 +440,217 @@ pub fn shapes_cube<B, S, R>(bounds: B, from_shape: S, shape_rot: R) -> Scheme
 	shapes_cube_combiner(bounds, from_shape, shape_rot).compile().unwrap().0
 }
 
+/// ***Inputs***: _ (`width` bits).
+///
+/// ***Outputs***: _ (`width` bits).
+
+///
+/// Row of timers, every one of which delays its bit by `ticks`.
+///
+/// Time complexity: `ticks + 1` ticks (timer itself takes 1 tick).
+pub fn timer_bus(width: u32, ticks: u32) -> Scheme {
+	timer_ramp(width, ticks, 0)
+}
+
+/// ***Inputs***: _ (`width` bits).
+///
+/// ***Outputs***: _ (`width` bits).
+
+///
+/// Row of timers, where bit `i` is delayed by `base + i * step` ticks.
+/// `step` can be negative, but no delay can be less than zero.
+///
+/// Time complexity: `delay + 1` ticks for every bit (timer itself
+/// takes 1 tick).
+///
+/// # Example
+/// ```
+/// # use sm_logic::presets::timer_ramp;
+/// # use sm_logic::testing::Simulator;
+/// // Bit 0 is delayed by 4 ticks, bit 1 by 2, bit 2 by 0
+/// let mut sim = Simulator::new(&timer_ramp(3, 4, -2));
+///
+/// sim.set_input_number("_", 0b111).unwrap();
+/// sim.run(1);
+/// assert_eq!(sim.output_number("_").unwrap(), 0b100);
+/// sim.run(2);
+/// assert_eq!(sim.output_number("_").unwrap(), 0b110);
+/// sim.run(2);
+/// assert_eq!(sim.output_number("_").unwrap(), 0b111);
+/// ```
+pub fn timer_ramp(width: u32, base: u32, step: i32) -> Scheme {
+	let mut combiner = Combiner::pos_manual();
+	combiner.set_debug_name("presets::timer_ramp");
+
+	let mut input = Bind::new("_", "binary", (width, 1, 1));
+	let mut output = Bind::new("_", "binary", (width, 1, 1));
+
+	for i in 0..width {
+		let delay = base as i64 + i as i64 * step as i64;
+		if delay < 0 {
+			panic!("Timer ramp gives negative delay {} to bit {}", delay, i);
+		}
+
+		let name = format!("timer_{}", i);
+		combiner.add(&name, Timer::new(delay as u32)).unwrap();
+		combiner.pos().place_last((i as i32, 0, 0));
+
+		input.connect(((i as i32, 0, 0), (1, 1, 1)), &name);
+		output.connect(((i as i32, 0, 0), (1, 1, 1)), &name);
+	}
+
+	input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input).unwrap();
+	combiner.bind_output(output).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
 pub fn shift_connection(shift: (i32, i32, i32)) -> Box<dyn Connection> {
 	ConnMap::new(move |(point, _in_bounds), _out_bounds| Some(point + Point::from_tuple(shift)))
-}
\ No newline at end of file
+}
+
+/// ***Inputs***: _ (`from_width` bits).
+///
+/// ***Outputs***: _ (`to_width` bits).
+
+///
+/// Extends binary number to a wider bus. If `signed`, the highest bit
+/// of the input (sign bit) is repeated in all the upper bits of the
+/// output, otherwise upper bits are always zero.
+///
+/// Time complexity: 1 tick for the lower bits, 2 ticks for the upper
+/// bits (plus 1 tick, if the upper part is wider than
+/// [`MAX_CONNECTIONS`]).
+///
+/// # Example
+/// ```
+/// # use sm_logic::presets::extend;
+/// # use sm_logic::testing::Simulator;
+/// let mut sim = Simulator::new(&extend(4, 8, true));
+///
+/// // -3
+/// sim.set_input_number("_", 0b1101).unwrap();
+/// sim.run(2);
+/// assert_eq!(sim.output_number("_").unwrap(), 0b1111_1101);
+/// ```
+pub fn extend(from_width: u32, to_width: u32, signed: bool) -> Scheme {
+	if from_width == 0 || to_width < from_width {
+		panic!("Cannot extend {} bits to {} bits", from_width, to_width);
+	}
+
+	let mut combiner = Combiner::pos_manual();
+
+	combiner.add_shapes_cube("low", (from_width, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+
+	let mut output = Bind::new("_", "binary", (to_width, 1, 1));
+	output.connect(((0, 0, 0), (from_width, 1, 1)), "low");
+
+	let high_width = to_width - from_width;
+	if signed && high_width > 0 {
+		combiner.add_shapes_cube("high", (high_width, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+		combiner.pos().place_last((from_width as i32, 0, 0));
+
+		let sign_bit = format!("low/_/{}_0_0", from_width - 1);
+		if combiner.broadcast(&sign_bit, "high").unwrap().is_some() {
+			combiner.pos().place_last((from_width as i32, 1, 0));
+		}
+
+		output.connect(((from_width as i32, 0, 0), (high_width, 1, 1)), "high");
+	}
+
+	output.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_output(output).unwrap();
+
+	let mut input = Bind::new("_", "binary", (from_width, 1, 1));
+	input.connect_full("low");
+	input.gen_point_sectors("bit", |x, _y, _z| x.to_string()).unwrap();
+	combiner.bind_input(input).unwrap();
+
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	scheme
+}
+
+#[test]
+fn extend_test() {
+	use crate::testing::Simulator;
+
+	let mut signed = Simulator::new(&extend(4, 8, true));
+	let mut unsigned = Simulator::new(&extend(4, 8, false));
+
+	for number in 0..16u64 {
+		signed.set_input_number("_", number).unwrap();
+		unsigned.set_input_number("_", number).unwrap();
+		signed.run(2);
+		unsigned.run(2);
+
+		let expected = if number >= 8 { number | 0b1111_0000 } else { number };
+		assert_eq!(signed.output_number("_").unwrap(), expected);
+		assert_eq!(unsigned.output_number("_").unwrap(), number);
+	}
+
+	// Sign bit is repeated through a line of repeaters
+	let mut wide = Simulator::new(&extend(4, 300, true));
+	wide.set_input_number("_", 0b1000).unwrap();
+	wide.run(3);
+	assert!(wide.output("_").unwrap()[3..].iter().all(|bit| *bit));
+	assert!(wide.output("_").unwrap()[..3].iter().all(|bit| !*bit));
+}
+
+#[test]
+fn timer_bus_test() {
+	use crate::testing::Simulator;
+
+	let bus = timer_bus(4, 3);
+	assert_eq!(bus.bounds().tuple(), (4, 1, 2));
+	bus.clone().to_json();
+
+	let mut sim = Simulator::new(&bus);
+	sim.set_input_number("_", 0b1011).unwrap();
+	sim.run(3);
+	assert_eq!(sim.output_number("_").unwrap(), 0);
+	sim.run(1);
+	assert_eq!(sim.output_number("_").unwrap(), 0b1011);
+	assert_eq!(sim.output("_/2").unwrap(), vec![false]);
+}
+
+#[test]
+fn connect_tree_test() {
+	use crate::testing::Simulator;
+
+	let mut combiner = Combiner::pos_manual();
+	combiner.add_shapes_cube("targets", (600, 1, 1), OR, Facing::PosZ.to_rot()).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+
+	let mut levels = vec![];
+	let root = connect_tree(
+		&mut combiner,
+		(0..600).map(|i| format!("targets/_/{}_0_0", i)),
+		|combiner, level, index| {
+			let name = format!("node_{}_{}", level, index);
+			combiner.add(&name, OR).unwrap();
+			combiner.pos().place_last((index as i32, 1 + level as i32, 0));
+			levels.push(level);
+			name
+		},
+		false
+	).unwrap();
+	assert_eq!(levels, vec![0, 0, 0, 1]);
+
+	combiner.pass_input("_", root, None as Option<String>).unwrap();
+	combiner.pass_output("_", "targets", None as Option<String>).unwrap();
+	let (scheme, _invalid) = combiner.compile().unwrap();
+	assert!(scheme.shapes().iter().all(|(_, _, shape)| shape.connections().len() <= MAX_CONNECTIONS as usize));
+
+	let mut sim = Simulator::new(&scheme);
+	sim.set_input("_", &[true]).unwrap();
+	sim.run(3);
+	assert!(sim.output("_").unwrap().into_iter().all(|bit| bit));
+
+	let mut combiner = Combiner::pos_manual();
+	let root = connect_tree(&mut combiner, Vec::<String>::new(), |_, _, _| "node", false);
+	assert!(matches!(root, Err(Error::NoTargets { .. })));
+}
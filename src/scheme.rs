@@ -1,15 +1,41 @@
+use std::collections::HashMap;
+use std::io::Write;
+#[cfg(feature = "flate2")]
+use flate2::{Compression, write::GzEncoder};
+use std::path::Path;
 use json::{JsonValue, object};
+use uuid::Uuid;
+use crate::bp_manager::{description_json, NO_DESCRIPTION};
+use crate::combiner::SlotSide;
 use crate::shape::Shape;
-use crate::shape::vanilla::{BlockBody, BlockType};
+use crate::shape::vanilla::{BlockBody, BlockType, GateMode};
 use crate::slot::{Slot, SlotSector};
-use crate::util::{Bounds};
+use crate::util::{Bounds, MAX_CONNECTIONS};
 use crate::util::palette::{input_color, output_color};
 use crate::util::split_first_token;
-use crate::util::Rot;
+use crate::util::{Rot, RotError};
 use crate::util::Point;
 
 pub const DEFAULT_SLOT: &str = "_";
 
+/// Problem with a slot of the scheme, found by [`Scheme::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlotIssue {
+	/// Point of the slot references shape, that does not exist.
+	InvalidShapeId {
+		slot: String,
+		side: SlotSide,
+		point: Point,
+		shape_id: usize,
+	},
+
+	/// Slot is not connected to any shape.
+	EmptySlot {
+		slot: String,
+		side: SlotSide,
+	},
+}
+
 /// Some structure/creation/blueprint made up of in-game
 /// blocks and parts.
 ///
@@ -27,6 +53,11 @@ pub struct Scheme {
 	inputs: Vec<Slot>,
 	outputs: Vec<Slot>,
 	bounds: Bounds,
+
+	/// Debug name of the `Combiner`, that created the scheme.
+	origin: Option<String>,
+	/// Human readable description, is not a part of blueprint JSON.
+	description: Option<String>,
 }
 
 impl Scheme {
@@ -41,6 +72,8 @@ impl Scheme {
 			inputs,
 			outputs,
 			bounds: (0, 0, 0).into(),
+			origin: None,
+			description: None,
 		};
 		scheme.set_bounds();
 		scheme
@@ -52,19 +85,36 @@ impl Scheme {
 			inputs: vec![],
 			outputs: vec![],
 			bounds: (0, 0, 0).into(),
+			origin: None,
+			description: None,
 		}
 	}
 
 	/// Rotates whole Scheme / rotates every [`Shape`] of it.
 	pub fn rotate(&mut self, rot: Rot) {
+		self.rotate_around(rot, Point::new(0, 0, 0));
+	}
+
+	/// Same as [`Scheme::rotate`], but rotates around `pivot` point
+	/// instead of (0, 0, 0). Shape at `pivot` stays at its place.
+	pub fn rotate_around(&mut self, rot: Rot, pivot: Point) {
 		let global_rot = rot;
 		for (pos, rot, _) in &mut self.shapes {
-			*pos = global_rot.apply(*pos);
+			*pos = pivot + global_rot.apply(*pos - pivot);
 			*rot = global_rot.apply_to_rot(rot.clone());
 		}
 		self.set_bounds();
 	}
 
+	/// Moves every [`Shape`] of the Scheme by `offset`. Slots are not
+	/// changed, since they reference shapes by ids.
+	pub fn translate(&mut self, offset: Point) {
+		for (pos, _, _) in &mut self.shapes {
+			*pos += offset;
+		}
+		self.set_bounds();
+	}
+
 	/// Returns all the inputs of the Scheme.
 	pub fn inputs(&self) -> &Vec<Slot> {
 		&self.inputs
@@ -122,10 +172,76 @@ impl Scheme {
 		&self.shapes
 	}
 
+	/// Counts shapes of the scheme by their kind (see [`Shape::kind`]).
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::presets::math::adder;
+	/// let histogram = adder(8).shape_histogram();
+	///
+	/// assert_eq!(histogram.get("gate"), Some(&56));
+	/// assert_eq!(histogram.get("timer"), None);
+	/// ```
+	pub fn shape_histogram(&self) -> HashMap<String, usize> {
+		let mut histogram: HashMap<String, usize> = HashMap::new();
+
+		for (_, _, shape) in &self.shapes {
+			*histogram.entry(shape.kind().to_string()).or_insert(0) += 1;
+		}
+
+		histogram
+	}
+
+	/// Counts logic gates of the scheme by their [`GateMode`].
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::presets::math::adder;
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// let histogram = adder(8).gate_histogram();
+	///
+	/// assert_eq!(histogram.get(&GateMode::AND), Some(&24));
+	/// assert_eq!(histogram.get(&GateMode::XOR), Some(&8));
+	/// assert_eq!(histogram.get(&GateMode::NOR), None);
+	/// ```
+	pub fn gate_histogram(&self) -> HashMap<GateMode, usize> {
+		let mut histogram: HashMap<GateMode, usize> = HashMap::new();
+
+		for (_, _, shape) in &self.shapes {
+			if let Some(mode) = shape.gate_mode() {
+				*histogram.entry(mode).or_insert(0) += 1;
+			}
+		}
+
+		histogram
+	}
+
 	pub fn bounds(&self) -> Bounds {
 		self.bounds.clone()
 	}
 
+	/// Returns debug name of the `Combiner`, that created the scheme
+	/// (see `Combiner::set_debug_name`).
+	pub fn origin(&self) -> &Option<String> {
+		&self.origin
+	}
+
+	pub fn set_origin<S: Into<String>>(&mut self, name: S) {
+		self.origin = Some(name.into());
+	}
+
+	/// Returns description of the scheme (see [`Scheme::set_description`]).
+	pub fn description(&self) -> &Option<String> {
+		&self.description
+	}
+
+	/// Sets human readable description of the scheme. It is not exported
+	/// into blueprint JSON, but [`crate::bp_manager::BPManager::save_scheme`]
+	/// writes it into `description.json` of the blueprint.
+	pub fn set_description<S: Into<String>>(&mut self, text: S) {
+		self.description = Some(text.into());
+	}
+
 	/// Sets color of every shape to a given color.
 	/// Basically just fills everything with color.
 	pub fn full_paint<S: Into<String>>(&mut self, color: S) {
@@ -148,22 +264,192 @@ impl Scheme {
 		}
 	}
 
+	/// Just like [`Scheme::soft_paint`], but the color of each shape is
+	/// chosen by `color(pos, bounds)`, where `pos` is the position of
+	/// the shape relative to the scheme corner (so it is inside of
+	/// `bounds`) and `bounds` is the size of the scheme.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::presets::math::adder;
+	/// let mut scheme = adder(8);
+	/// // Left half is red, right half is blue
+	/// scheme.paint_gradient(|pos, bounds| {
+	/// 	if *pos.x() * 2 < *bounds.x() as i32 { "ff0000".to_string() } else { "0000ff".to_string() }
+	/// });
+	///
+	/// assert!(scheme.shapes().iter().all(|(_, _, shape)| shape.get_color().is_some()));
+	/// ```
+	pub fn paint_gradient<F: Fn(Point, Bounds) -> String>(&mut self, color: F) {
+		let (start, bounds) = self.calculate_bounds();
+
+		for (pos, _, shape) in &mut self.shapes {
+			if shape.get_color().is_none() {
+				shape.set_color(color(*pos - start, bounds));
+			}
+		}
+	}
+
+	/// Sets color of every shape, that is referenced by the given slot
+	/// (or its sector). Inputs are searched first, then outputs.
+	/// Returns false if there is no such slot.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::scheme::Scheme;
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// let mut scheme: Scheme = GateMode::AND.into();
+	///
+	/// assert!(scheme.paint_slot("_", "ff0000"));
+	/// assert!(!scheme.paint_slot("no such slot", "ff0000"));
+	/// assert_eq!(scheme.shapes()[0].2.get_color(), &Some("ff0000".to_string()));
+	/// ```
+	pub fn paint_slot<N, S>(&mut self, name: N, color: S) -> bool
+		where N: Into<String>,
+			  S: Into<String>
+	{
+		let name = name.into();
+		let color = color.into();
+
+		let slot = match self.input(name.clone()) {
+			Some(slot) => Some(slot),
+			None => self.output(name),
+		};
+
+		let ids: Vec<usize> = match slot {
+			None => return false,
+			Some((slot, sector)) => sector.bounds.points()
+				.filter_map(|point| slot.get_point(sector.pos + point))
+				.flatten()
+				.cloned()
+				.collect(),
+		};
+
+		for id in ids {
+			self.shapes[id].2.set_color(&color);
+		}
+
+		true
+	}
+
 	/// Shifts, rotates and offsets controller ids, then returns raw data:
 	///
 	/// (shapes, inputs, outputs)
 	pub fn disassemble(mut self, start_shape: usize, pos: Point, rot: Rot) -> (Vec<(Point, Rot, Shape)>, Vec<Slot>, Vec<Slot>) {
 		let (start, _) = self.calculate_bounds();
 
-		for (shape_pos, shape_rot, shape) in &mut self.shapes {
+		for (shape_pos, shape_rot, _) in &mut self.shapes {
 			*shape_rot = rot.apply_to_rot(shape_rot.clone());
 			*shape_pos = pos + rot.apply(*shape_pos - start);
+		}
+		self.offset_connections(start_shape);
+
+		(self.shapes, self.inputs, self.outputs)
+	}
+
+	/// Adds `offset` to every controller id of the scheme: to all the
+	/// connections of all the shapes and to all the slots shape maps.
+	/// Shapes themselves are not moved.
+	///
+	/// Useful when shapes of the scheme are going to be placed after
+	/// `offset` other shapes, e.g. when assembling schemes manually.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::scheme::Scheme;
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use sm_logic::util::Point;
+	/// let scheme: Scheme = GateMode::AND.into();
+	/// let scheme = scheme.with_id_offset(5);
+	///
+	/// assert_eq!(scheme.inputs()[0].get_point(Point::new_ng(0, 0, 0)), Some(&vec![5]));
+	/// assert_eq!(scheme.outputs()[0].get_point(Point::new_ng(0, 0, 0)), Some(&vec![5]));
+	/// ```
+	pub fn with_id_offset(mut self, offset: usize) -> Scheme {
+		self.offset_connections(offset);
+
+		for slot in self.inputs.iter_mut().chain(self.outputs.iter_mut()) {
+			for point in slot.shape_map_mut().as_raw_mut() {
+				for id in point.iter_mut() {
+					*id += offset;
+				}
+			}
+		}
+
+		self
+	}
 
+	fn offset_connections(&mut self, offset: usize) {
+		for (_, _, shape) in &mut self.shapes {
 			for connection in shape.connections_mut() {
-				*connection += start_shape;
+				*connection += offset;
 			}
 		}
+	}
 
-		(self.shapes, self.inputs, self.outputs)
+	/// Adds all the shapes and slots of `other` scheme to this one.
+	/// `other` is rotated by `rot` and its corner is placed at `offset`,
+	/// just like [`Scheme::disassemble`] does.
+	///
+	/// Slots are added as is, so slot names collisions are caller's
+	/// responsibility: if both schemes have slots with the same name,
+	/// only the slot of this scheme can be found by name.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::scheme::Scheme;
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use sm_logic::util::{Bounds, Point, Rot};
+	/// let mut scheme: Scheme = GateMode::AND.into();
+	/// let other: Scheme = GateMode::OR.into();
+	///
+	/// scheme.merge(other, Point::new_ng(2, 0, 0), Rot::new(0, 0, 0));
+	///
+	/// assert_eq!(scheme.shapes_count(), 2);
+	/// assert_eq!(scheme.bounds(), Bounds::new_ng(3, 1, 1));
+	/// assert_eq!(scheme.inputs().len(), 2);
+	/// assert_eq!(scheme.inputs()[1].get_point(Point::new_ng(0, 0, 0)), Some(&vec![1]));
+	/// ```
+	pub fn merge(&mut self, other: Scheme, offset: Point, rot: Rot) {
+		let start_shape = self.shapes_count();
+		let (shapes, inputs, outputs) = other
+			.with_id_offset(start_shape)
+			.disassemble(0, offset, rot);
+
+		self.shapes.extend(shapes);
+		self.inputs.extend(inputs);
+		self.outputs.extend(outputs);
+		self.set_bounds();
+	}
+
+	/// Adds solid block of the scheme size, expanded by `thickness` in
+	/// every direction, and moved by `offset`. The block is forcibly
+	/// used, so [`Scheme::remove_unused`] keeps it.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::presets::shapes_cube;
+	/// # use sm_logic::shape::vanilla::{BlockType, GateMode};
+	/// # use sm_logic::util::{Bounds, Point};
+	/// // Flat 4x4 screen with 1 block wide frame behind it
+	/// let mut screen = shapes_cube((4, 4, 1), GateMode::OR, (0, 0, 0));
+	/// screen.add_backing(BlockType::Concrete1, 1, Point::new_ng(0, 0, -2));
+	///
+	/// assert_eq!(screen.shapes_count(), 17);
+	/// assert_eq!(screen.shapes()[16].2.bounds(), Bounds::new_ng(6, 6, 3));
+	/// assert_eq!(screen.bounds(), Bounds::new_ng(6, 6, 4));
+	///
+	/// screen.remove_unused();
+	/// assert_eq!(screen.shapes_count(), 17);
+	/// ```
+	pub fn add_backing(&mut self, block: BlockType, thickness: u32, offset: Point) {
+		let (start, bounds) = self.calculate_bounds();
+
+		let mut backing = BlockBody::new(block, bounds + thickness * 2);
+		backing.set_forcibly_used();
+
+		self.shapes.push((start + offset - thickness as i32, Rot::new(0, 0, 0), backing));
+		self.set_bounds();
 	}
 
 	/// Converts [`Scheme`] to JSON blueprint.
@@ -171,68 +457,197 @@ impl Scheme {
 		self.to_json_custom_colors(input_color, output_color)
 	}
 
+	/// Writes blueprint JSON (see [`Scheme::to_json`]) compressed with
+	/// gzip. The game itself reads only plain `blueprint.json`, so this is
+	/// meant for archiving and sending big blueprints. To get the
+	/// blueprint back, unpack it with any gzip tool (`gzip -d`).
+	///
+	/// Requires `flate2` feature.
+	///
+	/// # Example
+	/// ```ignore
+	/// # use std::fs::File;
+	/// # use sm_logic::presets::math::adder;
+	/// let mut file = File::create("blueprint.json.gz").unwrap();
+	/// adder(8).write_json_gzip(&mut file).unwrap();
+	/// ```
+	#[cfg(feature = "flate2")]
+	pub fn write_json_gzip<W: Write>(self, w: &mut W) -> std::io::Result<()> {
+		let mut encoder = GzEncoder::new(w, Compression::default());
+		self.write_json(&mut encoder)?;
+		encoder.finish()?;
+		Ok(())
+	}
+
+	/// Converts [`Scheme`] to the pair of JSONs, that make up a blueprint
+	/// folder: `blueprint.json` and `description.json`. `uuid` is the
+	/// local id of the blueprint (and the name of its folder).
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::presets::math::adder;
+	/// let mut scheme = adder(4);
+	/// scheme.set_description("4 bit adder");
+	///
+	/// let uuid = "c7a7c0d4-0a5e-4a1c-a9c8-1b2a3f4e5d6c";
+	/// let (blueprint, description) = scheme.to_blueprint_files("adder", uuid);
+	///
+	/// assert!(blueprint.has_key("bodies"));
+	/// assert_eq!(description["name"], "adder");
+	/// assert_eq!(description["localId"], uuid);
+	/// assert_eq!(description["description"], "4 bit adder");
+	/// ```
+	pub fn to_blueprint_files(self, name: &str, uuid: &str) -> (JsonValue, JsonValue) {
+		let description = self.description.clone()
+			.unwrap_or(NO_DESCRIPTION.to_string());
+
+		(self.to_json(), description_json(name, uuid, &description))
+	}
+
+	/// Creates blueprint folder `dir` (if it does not exist) and writes
+	/// `blueprint.json` and `description.json` into it. If the name of
+	/// the folder is a UUID, it is used as local id of the blueprint,
+	/// otherwise random UUID is generated.
+	///
+	/// To save blueprint right into the game's blueprints folder by its
+	/// name, see [`crate::bp_manager::BPManager::save_scheme`].
+	pub fn write_to_folder(self, dir: &Path, name: &str) -> std::io::Result<()> {
+		let uuid = dir.file_name()
+			.and_then(|name| name.to_str())
+			.and_then(|name| Uuid::parse_str(name).ok())
+			.unwrap_or_else(Uuid::new_v4);
+
+		let (blueprint, description) = self.to_blueprint_files(name, &uuid.to_string());
+
+		std::fs::create_dir_all(dir)?;
+		std::fs::write(dir.join("blueprint.json"), blueprint.to_string())?;
+		std::fs::write(dir.join("description.json"), description.to_string())?;
+		Ok(())
+	}
+
+	/// Same as [`Scheme::to_json`], but instead of panicking on invalid
+	/// rotations (see [`Rot::is_valid`]) returns list of all the shapes,
+	/// that have them.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::scheme::Scheme;
+	/// # use sm_logic::shape::vanilla::{Gate, GateMode};
+	/// # use sm_logic::util::{Mat3x3, Point, Rot};
+	/// let scheme: Scheme = GateMode::AND.into();
+	/// assert!(scheme.try_to_json().is_ok());
+	///
+	/// let broken_rot = Rot::from_matrix(Mat3x3::unit(2));
+	/// let scheme = Scheme::create(
+	/// 	vec![(Point::new_ng(0, 0, 0), broken_rot, Gate::new(GateMode::OR))],
+	/// 	vec![], vec![]
+	/// );
+	/// let errors = scheme.try_to_json().unwrap_err();
+	/// assert_eq!(errors.len(), 1);
+	/// assert_eq!(errors[0].0, 0);
+	/// ```
+	pub fn try_to_json(self) -> Result<JsonValue, Vec<(usize, RotError)>> {
+		let errors: Vec<(usize, RotError)> = self.shapes.iter()
+			.enumerate()
+			.filter_map(|(id, (_, rot, _))|
+				rot.try_to_facing_orient().err().map(|error| (id, error))
+			)
+			.collect();
+
+		if !errors.is_empty() {
+			Err(errors)
+		} else {
+			Ok(self.to_json())
+		}
+	}
+
 	/// Converts [`Scheme`] to JSON blueprint.
 	pub fn to_json_custom_colors<P1, P2>(mut self, inputs_palette: P1, outputs_palette: P2) -> JsonValue
 		where P1: Fn(u32, Point) -> String,
 				P2: Fn(u32, Point) -> String,
 	{
-		let mut array: Vec<JsonValue> = Vec::new();
+		self.paint_slots(inputs_palette, outputs_palette);
 
-		// Slot
-		for (i, bind) in self.inputs.into_iter().enumerate() {
-			let map_size: (i32, i32, i32) = bind.shape_map().bounds().cast().tuple();
+		let mut array: Vec<JsonValue> = Vec::new();
+		for (i, (pos, rot, shape)) in self.shapes.into_iter().enumerate() {
+			array.push(shape.build(pos, rot, i));
+		}
 
-			// Point of slot
-			for x in 0..map_size.0 {
-				for y in 0..map_size.1 {
-					for z in 0..map_size.2 {
-						// All the connections of the point
-						for vec in bind.shape_map().get((x as usize, y as usize, z as usize)) {
-							// Connection of the point
-							for id in vec {
-								let (_, _, shape) = &mut self.shapes[*id];
-								shape.set_color(inputs_palette(i as u32, (x, y, z).into()));
-							}
-						}
-					}
+		let array = JsonValue::Array(array);
+		let mut obj = object!{
+			"bodies": [
+				{
 				}
+			],
+			"version": 4_i32
+		};
+		obj["bodies"][0]["childs"] = array;
+		obj
+	}
+
+	/// Writes JSON blueprint (same as [`Scheme::to_json`] gives) to `w`.
+	/// Shapes are converted to JSON and written one by one, so the whole
+	/// blueprint is never held in memory. Use it for big schemes.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::presets::math::adder;
+	/// let scheme = adder(4);
+	/// let mut written: Vec<u8> = Vec::new();
+	/// scheme.clone().write_json(&mut written).unwrap();
+	///
+	/// assert_eq!(String::from_utf8(written).unwrap(), scheme.to_json().dump());
+	/// ```
+	pub fn write_json<W: Write>(mut self, w: &mut W) -> std::io::Result<()> {
+		self.paint_slots(input_color, output_color);
+
+		w.write_all(b"{\"bodies\":[{\"childs\":[")?;
+		for (i, (pos, rot, shape)) in self.shapes.into_iter().enumerate() {
+			if i != 0 {
+				w.write_all(b",")?;
 			}
+			shape.build(pos, rot, i).write(w)?;
 		}
+		w.write_all(b"]}],\"version\":4}")?;
 
-		for (i, bind) in self.outputs.into_iter().enumerate() {
-			let map_size: (i32, i32, i32) = bind.shape_map().bounds().cast().tuple();
+		Ok(())
+	}
 
-			// Point of slot
+	/// Paints shapes of the input and output slots with given palettes.
+	fn paint_slots<P1, P2>(&mut self, inputs_palette: P1, outputs_palette: P2)
+		where P1: Fn(u32, Point) -> String,
+				P2: Fn(u32, Point) -> String,
+	{
+		let slots = self.inputs.iter()
+			.map(|slot| (slot, &inputs_palette as &dyn Fn(u32, Point) -> String))
+			.enumerate()
+			.chain(
+				self.outputs.iter()
+					.map(|slot| (slot, &outputs_palette as &dyn Fn(u32, Point) -> String))
+					.enumerate()
+			);
+
+		for (i, (slot, palette)) in slots {
+			let map_size: (i32, i32, i32) = slot.shape_map().bounds().cast().tuple();
+
+			// Point of slot. Order matters, if some shapes belong to
+			// several points: the last color is kept
 			for x in 0..map_size.0 {
 				for y in 0..map_size.1 {
 					for z in 0..map_size.2 {
-						// All the connections of the point
-						for vec in bind.shape_map().get((x as usize, y as usize, z as usize)) {
-							// Connection of the point
-							for id in vec {
+						// All the shapes of the point
+						if let Some(ids) = slot.shape_map().get((x as usize, y as usize, z as usize)) {
+							let color = palette(i as u32, (x, y, z).into());
+
+							for id in ids {
 								let (_, _, shape) = &mut self.shapes[*id];
-								shape.set_color(outputs_palette(i as u32, (x, y, z).into()));
+								shape.set_color(color.clone());
 							}
 						}
 					}
 				}
 			}
 		}
-
-		for (i, (pos, rot, shape)) in self.shapes.into_iter().enumerate() {
-			array.push(shape.build(pos, rot, i));
-		}
-
-		let array = JsonValue::Array(array);
-		let mut obj = object!{
-			"bodies": [
-				{
-				}
-			],
-			"version": 4_i32
-		};
-		obj["bodies"][0]["childs"] = array;
-		obj
 	}
 
 	pub fn filter_shapes<F>(&mut self, filter: F)
@@ -332,6 +747,11 @@ impl Scheme {
 		self.set_bounds();
 	}
 
+	/// Replaces shapes, that do not affect outputs, with blocks of the
+	/// same size and position. Connections of the replaced shapes are
+	/// deleted, and the blocks are still unused, so subsequent
+	/// [`Scheme::remove_unused`] removes them (and bounds may shrink).
+	/// Use [`Scheme::replace_unused_keep_bounds`] to keep them.
 	pub fn replace_unused_with(&mut self, block: BlockType) {
 		let is_used = self.get_used_shapes();
 
@@ -342,6 +762,181 @@ impl Scheme {
 		}
 	}
 
+	/// Same as [`Scheme::replace_unused_with`], but the blocks are
+	/// marked forcibly used, so they stay in the scheme as spacers after
+	/// [`Scheme::remove_unused`], and bounds of the scheme do not change.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::combiner::Combiner;
+	/// # use sm_logic::shape::vanilla::{BlockType, GateMode};
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_iter([("used", GateMode::OR), ("unused", GateMode::AND)]).unwrap();
+	/// combiner.pos().place_iter([("used", (0, 0, 0)), ("unused", (0, 0, 5))]);
+	/// combiner.pass_output("_", "used", None as Option<String>).unwrap();
+	/// let (scheme, _) = combiner.compile().unwrap();
+	///
+	/// let mut removed = scheme.clone();
+	/// removed.replace_unused_with(BlockType::Glass);
+	/// removed.remove_unused();
+	/// assert_eq!(removed.bounds().tuple(), (1, 1, 1));
+	///
+	/// let mut kept = scheme.clone();
+	/// kept.replace_unused_keep_bounds(BlockType::Glass);
+	/// kept.remove_unused();
+	/// assert_eq!(kept.shapes_count(), 2);
+	/// assert_eq!(kept.bounds().tuple(), (1, 1, 6));
+	/// ```
+	pub fn replace_unused_keep_bounds(&mut self, block: BlockType) {
+		let is_used = self.get_used_shapes();
+
+		for i in (0..is_used.len()).rev() {
+			if !is_used[i] {
+				self.replace_shape(i, block);
+				self.shapes[i].2.set_forcibly_used();
+			}
+		}
+	}
+
+	/// Checks slots of the scheme without consuming it. Reports slot
+	/// points, that reference non-existent shapes, and slots, that are
+	/// not connected to any shape.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::combiner::SlotSide;
+	/// # use sm_logic::scheme::{Scheme, SlotIssue};
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use sm_logic::slot::Slot;
+	/// # use sm_logic::util::{Map3D, Point};
+	/// let gate: Scheme = GateMode::AND.into();
+	/// assert!(gate.validate().is_empty());
+	///
+	/// let (shapes, inputs, _) = gate.disassemble(0, Point::new_ng(0, 0, 0), (0, 0, 0).into());
+	/// let outputs = vec![
+	/// 	Slot::new("dangling".to_string(), "bit".to_string(), (1, 1, 1).into(), Map3D::filled((1, 1, 1), vec![5])),
+	/// 	Slot::new("empty".to_string(), "bit".to_string(), (1, 1, 1).into(), Map3D::filled((1, 1, 1), vec![])),
+	/// ];
+	/// let broken = Scheme::create(shapes, inputs, outputs);
+	///
+	/// assert_eq!(broken.validate(), vec![
+	/// 	SlotIssue::InvalidShapeId {
+	/// 		slot: "dangling".to_string(),
+	/// 		side: SlotSide::Output,
+	/// 		point: Point::new(0, 0, 0),
+	/// 		shape_id: 5,
+	/// 	},
+	/// 	SlotIssue::EmptySlot {
+	/// 		slot: "empty".to_string(),
+	/// 		side: SlotSide::Output,
+	/// 	},
+	/// ]);
+	/// ```
+	pub fn validate(&self) -> Vec<SlotIssue> {
+		let mut issues: Vec<SlotIssue> = vec![];
+
+		let sides = [(SlotSide::Input, &self.inputs), (SlotSide::Output, &self.outputs)];
+		for (side, slots) in sides {
+			for slot in slots {
+				let mut is_empty = true;
+
+				for (pos, shapes) in slot.shape_map().iter() {
+					for shape_id in shapes {
+						is_empty = false;
+
+						if *shape_id >= self.shapes.len() {
+							issues.push(SlotIssue::InvalidShapeId {
+								slot: slot.name().clone(),
+								side,
+								point: Point::new(pos.0 as i32, pos.1 as i32, pos.2 as i32),
+								shape_id: *shape_id,
+							});
+						}
+					}
+				}
+
+				if is_empty {
+					issues.push(SlotIssue::EmptySlot {
+						slot: slot.name().clone(),
+						side,
+					});
+				}
+			}
+		}
+
+		issues
+	}
+
+	/// Splits shapes into groups, which are connected with each other.
+	/// Connections are treated as undirected edges. Every group is
+	/// sorted by shape id, and groups are sorted by their first id.
+	///
+	/// More than one group usually means, that some part of the logic
+	/// is not wired to the rest of it.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::combiner::Combiner;
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_iter([("a", GateMode::OR), ("b", GateMode::AND), ("island", GateMode::OR)]).unwrap();
+	/// combiner.connect("b", "a");
+	/// combiner.pos().place_iter([("a", (0, 0, 0)), ("b", (1, 0, 0)), ("island", (2, 0, 0))]);
+	/// let (scheme, _) = combiner.compile().unwrap();
+	///
+	/// let components = scheme.connected_components();
+	/// assert_eq!(components.len(), 2);
+	/// assert!(components.iter().any(|group| group.len() == 1));
+	/// ```
+	pub fn connected_components(&self) -> Vec<Vec<usize>> {
+		fn find(parents: &mut [usize], id: usize) -> usize {
+			let mut root = id;
+			while parents[root] != root {
+				root = parents[root];
+			}
+
+			// Path compression
+			let mut id = id;
+			while parents[id] != root {
+				let next = parents[id];
+				parents[id] = root;
+				id = next;
+			}
+
+			root
+		}
+
+		let count = self.shapes.len();
+		let mut parents: Vec<usize> = (0..count).collect();
+
+		for (id, (_, _, shape)) in self.shapes.iter().enumerate() {
+			for target in shape.connections() {
+				if *target >= count {
+					continue;
+				}
+
+				let (a, b) = (find(&mut parents, id), find(&mut parents, *target));
+				if a != b {
+					parents[a.max(b)] = a.min(b);
+				}
+			}
+		}
+
+		// Roots are the smallest ids of the groups, so groups come sorted
+		let mut groups: Vec<Vec<usize>> = vec![];
+		let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+		for id in 0..count {
+			let root = find(&mut parents, id);
+			let group = *group_of_root.entry(root).or_insert_with(|| {
+				groups.push(vec![]);
+				groups.len() - 1
+			});
+			groups[group].push(id);
+		}
+
+		groups
+	}
+
 	fn get_used_shapes(&self) -> Vec<bool> {
 		// used = connected to output
 		let mut is_used: Vec<bool> = self.shapes.iter().map(
@@ -383,6 +978,136 @@ impl Scheme {
 		is_used
 	}
 
+	/// Merges logic gates, that have the same mode, the same initial state
+	/// and exactly the same incoming connections: only one of them is kept, and it gets all
+	/// the outgoing connections of the others. Repeats until there is
+	/// nothing to merge, since merged gates can make next gates identical.
+	///
+	/// Gates of input slots are not merged (they can get signals from
+	/// outside), gates of output slots are never removed. Gates are not
+	/// merged if the kept gate would get more than [`MAX_CONNECTIONS`]
+	/// outgoing connections. Returns count of removed gates.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::GateMode;
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add_shapes_cube("input", (2, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	/// combiner.add("and_0", GateMode::AND).unwrap();
+	/// combiner.add("and_1", GateMode::AND).unwrap();
+	/// combiner.add("output", GateMode::OR).unwrap();
+	/// combiner.dim_iter(["input"], ["and_0", "and_1"], (true, true, true));
+	/// combiner.connect_iter(["and_0", "and_1"], ["output"]);
+	/// combiner.pos().place_iter([
+	/// 	("input", (0, 0, 0)),
+	/// 	("and_0", (0, 1, 0)),
+	/// 	("and_1", (1, 1, 0)),
+	/// 	("output", (0, 2, 0)),
+	/// ]);
+	/// combiner.pass_input("_", "input", None as Option<String>).unwrap();
+	/// combiner.pass_output("_", "output", None as Option<String>).unwrap();
+	/// let (mut scheme, _) = combiner.compile().unwrap();
+	///
+	/// assert_eq!(scheme.dedup_gates(), 1);
+	/// assert_eq!(scheme.shapes_count(), 4);
+	/// ```
+	pub fn dedup_gates(&mut self) -> usize {
+		let mut removed = 0;
+
+		loop {
+			let duplicates = self.find_duplicate_gates();
+			if duplicates.is_empty() {
+				break;
+			}
+
+			// Moving outgoing connections to representatives
+			for (duplicate, representative) in &duplicates {
+				let conns = self.shapes[*duplicate].2.connections().clone();
+				self.shapes[*representative].2.extend_conn(conns);
+			}
+
+			let replaced: HashMap<usize, usize> = duplicates.iter().cloned().collect();
+			for (_, _, shape) in &mut self.shapes {
+				let mut conns: Vec<usize> = vec![];
+				for target in shape.connections() {
+					let target = *replaced.get(target).unwrap_or(target);
+					if !conns.contains(&target) {
+						conns.push(target);
+					}
+				}
+				*shape.connections_mut() = conns;
+			}
+
+			let mut ids: Vec<usize> = duplicates.into_iter().map(|(duplicate, _)| duplicate).collect();
+			ids.sort();
+			for id in ids.into_iter().rev() {
+				self.no_bounds_remove_shape(id);
+				removed += 1;
+			}
+		}
+
+		self.set_bounds();
+		removed
+	}
+
+	/// Pairs of (duplicate gate, gate to replace it with).
+	fn find_duplicate_gates(&self) -> Vec<(usize, usize)> {
+		let is_input = slots_shapes_mask(&self.inputs, self.shapes.len());
+		let is_output = slots_shapes_mask(&self.outputs, self.shapes.len());
+
+		let mut sources: Vec<Vec<usize>> = vec![vec![]; self.shapes.len()];
+		for (id, (_, _, shape)) in self.shapes.iter().enumerate() {
+			for target in shape.connections() {
+				sources[*target].push(id);
+			}
+		}
+
+		let mut representatives: HashMap<(GateMode, bool, Vec<usize>), usize> = HashMap::new();
+		// Outgoing connections representatives will have after the merge
+		let mut merged_conns: HashMap<usize, Vec<usize>> = HashMap::new();
+		let mut duplicates = vec![];
+
+		for (id, (_, _, shape)) in self.shapes.iter().enumerate() {
+			if is_input[id] || sources[id].is_empty() {
+				continue;
+			}
+
+			let mode = match shape.gate_mode() {
+				Some(mode) => mode,
+				None => continue,
+			};
+
+			let mut gate_sources = std::mem::take(&mut sources[id]);
+			gate_sources.sort();
+			gate_sources.dedup();
+
+			let key = (mode, shape.initial_state(), gate_sources);
+			match representatives.get(&key) {
+				Some(representative) if !is_output[id] => {
+					let conns = merged_conns.entry(*representative)
+						.or_insert_with(|| self.shapes[*representative].2.connections().clone());
+
+					let mut new_conns = conns.clone();
+					for target in shape.connections() {
+						if !new_conns.contains(target) {
+							new_conns.push(*target);
+						}
+					}
+
+					if new_conns.len() <= MAX_CONNECTIONS as usize {
+						*conns = new_conns;
+						duplicates.push((id, *representative));
+					}
+				},
+				Some(_) => {},
+				None => { representatives.insert(key, id); },
+			}
+		}
+
+		duplicates
+	}
+
 	pub fn set_forcibly_used(&mut self) {
 		for (_, _, shape) in &mut self.shapes {
 			shape.set_forcibly_used();
@@ -394,6 +1119,188 @@ impl Scheme {
 			shape.unset_forcibly_used();
 		}
 	}
+
+	/// Returns count of ticks of the longest signal path, that starts at
+	/// the shape with given id (delay of the shape itself included).
+	/// Every shape takes [`Shape::delay`] ticks to pass the signal.
+	///
+	/// Returns `None` if there is no such shape, or if the signal can
+	/// reach a loop (memory cell, for example) from it.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::combiner::Combiner;
+	/// # use crate::sm_logic::shape::vanilla::{GateMode, Timer};
+	/// let mut combiner = Combiner::pos_manual();
+	/// combiner.add("a", GateMode::AND).unwrap();
+	/// combiner.add("timer", Timer::new(5)).unwrap();
+	/// combiner.add("b", GateMode::AND).unwrap();
+	/// combiner.connect("a", "timer");
+	/// combiner.connect("timer", "b");
+	/// combiner.pos().place_iter([
+	/// 	("a", (0, 0, 0)),
+	/// 	("timer", (1, 0, 0)),
+	/// 	("b", (2, 0, 0)),
+	/// ]);
+	/// combiner.pass_input("_", "a", None as Option<String>).unwrap();
+	/// let (scheme, _) = combiner.compile().unwrap();
+	///
+	/// let (input, _) = scheme.input("_").unwrap();
+	/// let gate_a = input.shape_map().as_raw()[0][0];
+	///
+	/// // 1 tick for each gate, 6 ticks for the timer
+	/// assert_eq!(scheme.signal_delay(gate_a), Some(8));
+	/// assert_eq!(scheme.max_delay(), Ok(8));
+	/// ```
+	pub fn signal_delay(&self, from_shape: usize) -> Option<u32> {
+		if from_shape >= self.shapes.len() {
+			return None;
+		}
+
+		let mut delays = vec![None; self.shapes.len()];
+		self.longest_delays(from_shape, &mut delays).ok()?;
+		delays[from_shape]
+	}
+
+	/// Returns count of ticks of the longest signal path in the scheme.
+	/// Every shape takes [`Shape::delay`] ticks to pass the signal.
+	///
+	/// If there are loops (memory cells, for example), the path would be
+	/// infinite, so ids of the shapes of some found loop are returned
+	/// as an error.
+	pub fn max_delay(&self) -> Result<u32, Vec<usize>> {
+		let mut delays = vec![None; self.shapes.len()];
+
+		for id in 0..self.shapes.len() {
+			self.longest_delays(id, &mut delays)?;
+		}
+
+		Ok(delays.into_iter().flatten().max().unwrap_or(0))
+	}
+
+	/// Returns latencies of the slots: for each input slot - count of
+	/// ticks of the longest signal path from it to any output slot, for
+	/// each output slot - count of ticks of the longest signal path to it
+	/// from any input slot. Delays of the slots' shapes are included.
+	/// Slots, that are not connected to any slot of other side, are not
+	/// included.
+	///
+	/// Just like [`Scheme::max_delay`], returns ids of the shapes of some
+	/// loop as an error, if there are loops in the scheme.
+	pub fn slot_latencies(&self) -> Result<(HashMap<String, u32>, HashMap<String, u32>), Vec<usize>> {
+		// Loops would make latencies infinite
+		self.max_delay()?;
+		let order = self.topological_order();
+
+		let is_input = slots_shapes_mask(&self.inputs, self.shapes.len());
+		let is_output = slots_shapes_mask(&self.outputs, self.shapes.len());
+
+		// Longest path from the shape to any output shape
+		let mut to_output: Vec<Option<u32>> = vec![None; self.shapes.len()];
+		for id in order.iter().rev() {
+			let (_, _, shape) = &self.shapes[*id];
+			let longest_next = shape.connections().iter()
+				.filter_map(|target| to_output[*target])
+				.max();
+
+			to_output[*id] = match (longest_next, is_output[*id]) {
+				(None, false) => None,
+				(next, _) => Some(shape.delay() + next.unwrap_or(0)),
+			};
+		}
+
+		// Longest path from any input shape to the shape
+		let mut longest_prev: Vec<Option<u32>> = is_input.iter()
+			.map(|is_input| if *is_input { Some(0) } else { None })
+			.collect();
+		let mut from_input: Vec<Option<u32>> = vec![None; self.shapes.len()];
+		for id in order {
+			let (_, _, shape) = &self.shapes[id];
+			from_input[id] = longest_prev[id].map(|prev| prev + shape.delay());
+
+			for target in shape.connections() {
+				longest_prev[*target] = longest_prev[*target].max(from_input[id]);
+			}
+		}
+
+		Ok((slots_latencies(&self.inputs, &to_output), slots_latencies(&self.outputs, &from_input)))
+	}
+
+	/// Ids of all the shapes, sorted so that every shape goes before
+	/// the shapes it is connected to. Scheme must not contain loops.
+	fn topological_order(&self) -> Vec<usize> {
+		let mut incoming = vec![0usize; self.shapes.len()];
+		for (_, _, shape) in &self.shapes {
+			for target in shape.connections() {
+				incoming[*target] += 1;
+			}
+		}
+
+		let mut order: Vec<usize> = (0..self.shapes.len())
+			.filter(|id| incoming[*id] == 0)
+			.collect();
+
+		let mut i = 0;
+		while i < order.len() {
+			for target in self.shapes[order[i]].2.connections() {
+				incoming[*target] -= 1;
+				if incoming[*target] == 0 {
+					order.push(*target);
+				}
+			}
+			i += 1;
+		}
+
+		order
+	}
+
+	/// Depth-first search of longest signal paths from `start` shape.
+	/// Calculated delays are memoized in `delays`. Iterative, since
+	/// signal paths can be longer than the call stack allows.
+	fn longest_delays(&self, start: usize, delays: &mut [Option<u32>]) -> Result<(), Vec<usize>> {
+		if delays[start].is_some() {
+			return Ok(());
+		}
+
+		let mut in_stack = vec![false; self.shapes.len()];
+		// Shape id and count of its connections already visited
+		let mut stack: Vec<(usize, usize)> = vec![(start, 0)];
+		in_stack[start] = true;
+
+		while let Some((id, next)) = stack.last_mut() {
+			let id = *id;
+			let conns = self.shapes[id].2.connections();
+
+			if *next < conns.len() {
+				let target = conns[*next];
+				*next += 1;
+
+				if in_stack[target] {
+					let loop_start = stack.iter()
+						.position(|(id, _)| *id == target)
+						.unwrap();
+
+					return Err(stack[loop_start..].iter().map(|(id, _)| *id).collect());
+				}
+
+				if delays[target].is_none() {
+					in_stack[target] = true;
+					stack.push((target, 0));
+				}
+			} else {
+				let longest_next = conns.iter()
+					.filter_map(|target| delays[*target])
+					.max()
+					.unwrap_or(0);
+
+				delays[id] = Some(self.shapes[id].2.delay() + longest_next);
+				in_stack[id] = false;
+				stack.pop();
+			}
+		}
+
+		Ok(())
+	}
 }
 
 impl Scheme {
@@ -416,17 +1323,10 @@ impl Scheme {
 			let bounds_end = start + (rot.apply(shape.bounds().cast::<i32>() * 2 - 1) + 1) / 2;
 			let bounds_start = start + (rot.apply((-1, -1, -1).into()) + 1) / 2;
 
-			min = fold_coords(
-				min,
-				[start, bounds_start, bounds_end],
-				|a, b| if a < b { a } else { b }
-			);
-
-			max = fold_coords(
-				max,
-				[start, bounds_start, bounds_end],
-				|a, b| if a > b { a } else { b }
-			);
+			for point in [start, bounds_start, bounds_end] {
+				min = min.component_min(point);
+				max = max.component_max(point);
+			}
 		}
 
 		(min, (max - min).cast())
@@ -439,9 +1339,15 @@ impl Scheme {
 }
 
 pub fn find_slot<N: Into<String>>(name: N, slots: &Vec<Slot>) -> Option<&Slot> {
+	find_slot_or_default(name, slots, DEFAULT_SLOT)
+}
+
+/// Same as [`find_slot`], but empty name is replaced with `default_slot`
+/// instead of [`DEFAULT_SLOT`].
+pub fn find_slot_or_default<'a, N: Into<String>>(name: N, slots: &'a Vec<Slot>, default_slot: &str) -> Option<&'a Slot> {
 	let name = name.into();
 	let search_for = if name.len() == 0 {
-		DEFAULT_SLOT
+		default_slot
 	} else {
 		&name
 	};
@@ -455,20 +1361,275 @@ pub fn find_slot<N: Into<String>>(name: N, slots: &Vec<Slot>) -> Option<&Slot> {
 	None
 }
 
-/// Folds coordinates of all points separately by `fold` function
-fn fold_coords<P, I, F>(start_point: Point, points: I, fold: F) -> Point
-	where P: Into<Point>,
-		  I: IntoIterator<Item = P>,
-		  F: Fn(i32, i32) -> i32
-{
-	let (mut x, mut y, mut z) = start_point.tuple();
+/// For each shape id tells, if the shape belongs to any of the slots.
+fn slots_shapes_mask(slots: &[Slot], shapes_count: usize) -> Vec<bool> {
+	let mut mask = vec![false; shapes_count];
+	for slot in slots {
+		for shapes in slot.shape_map().as_raw() {
+			for shape in shapes {
+				mask[*shape] = true;
+			}
+		}
+	}
+	mask
+}
 
-	for point in points {
-		let (px, py, pz) = point.into().tuple();
-		x = fold(x, px);
-		y = fold(y, py);
-		z = fold(z, pz);
+/// Maximum of the shapes' latencies for each slot, that has any.
+fn slots_latencies(slots: &[Slot], latencies: &[Option<u32>]) -> HashMap<String, u32> {
+	slots.iter()
+		.filter_map(|slot| {
+			slot.shape_map().as_raw().iter()
+				.flatten()
+				.filter_map(|shape| latencies[*shape])
+				.max()
+				.map(|latency| (slot.name().clone(), latency))
+		})
+		.collect()
+}
+
+#[test]
+fn max_delay_test() {
+	use crate::presets::memory::xor_mem_cell;
+	use crate::presets::math::adder;
+
+	let adder = adder(4);
+	assert_eq!(adder.max_delay(), Ok(8));
+
+	let cell = xor_mem_cell(1);
+	let cycle = cell.max_delay().unwrap_err();
+	assert!(!cycle.is_empty());
+
+	for (i, id) in cycle.iter().enumerate() {
+		let next = cycle[(i + 1) % cycle.len()];
+		assert!(cell.shapes()[*id].2.connections().contains(&next));
 	}
+}
+
+#[test]
+fn slot_latencies_test() {
+	use crate::presets::math::adder;
+
+	let word_size = 4;
+	let (inputs, outputs) = adder(word_size).slot_latencies().unwrap();
+
+	assert_eq!(inputs.get("a"), Some(&(word_size * 2)));
+	assert_eq!(inputs.get("b"), Some(&(word_size * 2)));
+	assert_eq!(outputs.get("_"), Some(&(word_size * 2)));
+}
 
-	Point::new(x, y, z)
-}
\ No newline at end of file
+#[test]
+fn translate_test() {
+	use crate::presets::math::adder;
+
+	let mut scheme = adder(4);
+	let (start, bounds) = scheme.calculate_bounds();
+
+	scheme.translate(Point::new(5, 0, 0));
+
+	assert_eq!(scheme.calculate_bounds(), (start + Point::new(5, 0, 0), bounds));
+	assert_eq!(scheme.bounds(), bounds);
+}
+
+#[test]
+fn rotate_around_test() {
+	use crate::shape::vanilla::Gate;
+
+	let mut scheme = Scheme::create(
+		vec![
+			(Point::new(0, 0, 0), Rot::new(0, 0, 0), Gate::new(GateMode::AND)),
+			(Point::new(1, 0, 0), Rot::new(0, 0, 0), Gate::new(GateMode::OR)),
+		],
+		vec![],
+		vec![]
+	);
+
+	let rot = Rot::new(0, 0, 1);
+	scheme.rotate_around(rot.clone(), Point::new(1, 0, 0));
+
+	// Pivot gate stays in place, the other one goes around it
+	assert_eq!(scheme.shapes()[1].0, Point::new(1, 0, 0));
+	assert_eq!(scheme.shapes()[0].0, Point::new(1, -1, 0));
+	assert_eq!(scheme.shapes()[0].1, rot);
+	assert_eq!(scheme.shapes()[1].1, rot);
+	assert_eq!(scheme.bounds(), Bounds::new_ng(1, 2, 1));
+}
+
+#[test]
+fn validate_after_remove_unused_test() {
+	use crate::presets::math::multiplier;
+
+	let mut scheme = multiplier(4, 0);
+	scheme.remove_unused();
+	assert_eq!(scheme.validate(), vec![]);
+
+	scheme.replace_unused_with(BlockType::Glass);
+	assert_eq!(scheme.validate(), vec![]);
+}
+
+#[test]
+fn write_to_folder_test() {
+	use crate::presets::math::adder;
+
+	let uuid = Uuid::new_v4().to_string();
+	let folder = std::env::temp_dir().join(&uuid);
+	let scheme = adder(4);
+	scheme.clone().write_to_folder(&folder, "adder").unwrap();
+
+	let blueprint = std::fs::read_to_string(folder.join("blueprint.json")).unwrap();
+	assert_eq!(json::parse(&blueprint).unwrap(), scheme.to_json());
+
+	let description = std::fs::read_to_string(folder.join("description.json")).unwrap();
+	let description = json::parse(&description).unwrap();
+	assert_eq!(description["name"], "adder");
+	assert_eq!(description["localId"], uuid.as_str());
+
+	std::fs::remove_dir_all(&folder).unwrap();
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn write_json_gzip_test() {
+	use std::io::Read;
+	use flate2::read::GzDecoder;
+	use crate::presets::math::adder;
+
+	let scheme = adder(4);
+	let mut compressed: Vec<u8> = vec![];
+	scheme.clone().write_json_gzip(&mut compressed).unwrap();
+
+	let mut decompressed = String::new();
+	GzDecoder::new(compressed.as_slice()).read_to_string(&mut decompressed).unwrap();
+
+	assert_eq!(json::parse(&decompressed).unwrap(), scheme.to_json());
+}
+
+#[test]
+fn dedup_gates_test() {
+	use crate::combiner::Combiner;
+	use crate::shape::vanilla::GateMode;
+	use crate::testing::{exhaustive_inputs, Simulator};
+
+	// Two equal chains: after "and_*" gates are merged, "xor_*" gates
+	// become equal too. Outputs must stay.
+	let mut combiner = Combiner::pos_manual();
+	combiner.add_shapes_cube("input", (3, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+
+	for i in 0..2 {
+		combiner.add(format!("and_{}", i), GateMode::AND).unwrap();
+		combiner.pos().place_last((i, 1, 0));
+		combiner.add(format!("xor_{}", i), GateMode::XOR).unwrap();
+		combiner.pos().place_last((i, 2, 0));
+		combiner.add(format!("output_{}", i), GateMode::OR).unwrap();
+		combiner.pos().place_last((i, 3, 0));
+
+		combiner.connect("input/_/0_0_0", format!("and_{}", i));
+		combiner.connect("input/_/1_0_0", format!("and_{}", i));
+		combiner.connect(format!("and_{}", i), format!("xor_{}", i));
+		combiner.connect("input/_/2_0_0", format!("xor_{}", i));
+		combiner.connect(format!("xor_{}", i), format!("output_{}", i));
+		combiner.pass_output(format!("{}", i), format!("output_{}", i), None as Option<String>).unwrap();
+	}
+	combiner.pass_input("_", "input", None as Option<String>).unwrap();
+	let (original, _) = combiner.compile().unwrap();
+
+	let mut deduped = original.clone();
+	assert_eq!(deduped.dedup_gates(), 2);
+	assert_eq!(deduped.shapes_count(), original.shapes_count() - 2);
+	assert_eq!(deduped.dedup_gates(), 0);
+
+	let mut original = Simulator::new(&original);
+	let mut deduped = Simulator::new(&deduped);
+
+	for input in exhaustive_inputs(3) {
+		original.set_input("_", &input).unwrap();
+		deduped.set_input("_", &input).unwrap();
+		original.run(5);
+		deduped.run(5);
+
+		for output in ["0", "1"] {
+			assert_eq!(original.output(output).unwrap(), deduped.output(output).unwrap());
+		}
+	}
+}
+
+#[test]
+fn dedup_gates_initial_state_test() {
+	use crate::combiner::Combiner;
+	use crate::shape::vanilla::{Gate, GateMode};
+	use crate::testing::Simulator;
+
+	// Gates differ only by initial state, so they must not be merged
+	let mut combiner = Combiner::pos_manual();
+	combiner.add("input", GateMode::OR).unwrap();
+	combiner.add("active", Gate::with_initial(GateMode::OR, true)).unwrap();
+	combiner.add("inactive", GateMode::OR).unwrap();
+	combiner.add("output_0", GateMode::OR).unwrap();
+	combiner.add("output_1", GateMode::OR).unwrap();
+	combiner.connect_iter(["input"], ["active", "inactive"]);
+	combiner.connect("active", "output_0");
+	combiner.connect("inactive", "output_1");
+	combiner.pos().place_iter([
+		("input", (0, 0, 0)),
+		("active", (0, 1, 0)),
+		("inactive", (1, 1, 0)),
+		("output_0", (0, 2, 0)),
+		("output_1", (1, 2, 0)),
+	]);
+	combiner.pass_input("_", "input", None as Option<String>).unwrap();
+	combiner.pass_output("0", "output_0", None as Option<String>).unwrap();
+	combiner.pass_output("1", "output_1", None as Option<String>).unwrap();
+	let (mut scheme, _) = combiner.compile().unwrap();
+
+	assert_eq!(scheme.dedup_gates(), 0);
+	assert_eq!(scheme.shapes_count(), 5);
+
+	let mut sim = Simulator::new(&scheme);
+	sim.step();
+	assert_eq!(sim.output("0").unwrap(), vec![true]);
+	assert_eq!(sim.output("1").unwrap(), vec![false]);
+}
+
+#[test]
+fn dedup_gates_max_connections_test() {
+	use crate::combiner::Combiner;
+	use crate::shape::vanilla::GateMode;
+
+	// Merged gate would need 400 outgoing connections
+	let mut combiner = Combiner::pos_manual();
+	combiner.add("input", GateMode::OR).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+
+	for i in 0..2 {
+		let gate = format!("gate_{}", i);
+		let targets = format!("targets_{}", i);
+		combiner.add(&gate, GateMode::OR).unwrap();
+		combiner.pos().place_last((i, 1, 0));
+		combiner.add_shapes_cube(&targets, (200, 1, 1), GateMode::OR, (0, 0, 0)).unwrap();
+		combiner.pos().place_last((0, 2 + i, 0));
+
+		combiner.connect("input", &gate);
+		for j in 0..200 {
+			combiner.connect(&gate, format!("{}/_/{}_0_0", targets, j));
+		}
+		combiner.pass_output(&targets, &targets, None as Option<String>).unwrap();
+	}
+	combiner.pass_input("_", "input", None as Option<String>).unwrap();
+	let (mut scheme, _) = combiner.compile().unwrap();
+
+	assert_eq!(scheme.dedup_gates(), 0);
+	assert!(scheme.shapes().iter()
+		.all(|(_, _, shape)| shape.connections().len() <= MAX_CONNECTIONS as usize));
+}
+
+#[test]
+fn connected_components_test() {
+	use crate::presets::math::adder;
+
+	let mut scheme = adder(4);
+	let count = scheme.shapes_count();
+	assert_eq!(scheme.connected_components().len(), 1);
+
+	scheme.add_backing(BlockType::Glass, 0, Point::new_ng(0, 0, -10));
+	assert_eq!(scheme.connected_components(), vec![(0..count).collect(), vec![count]]);
+}
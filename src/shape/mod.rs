@@ -5,6 +5,7 @@ use dyn_clone::DynClone;
 use json::{JsonValue, object};
 use crate::scheme::{DEFAULT_SLOT, Scheme};
 use crate::slot::Slot;
+use crate::shape::vanilla::GateMode;
 
 use crate::util::{Map3D, Point};
 use crate::util::Rot;
@@ -21,13 +22,39 @@ use crate::util::Bounds;
 /// _`has_output`_ method should return if a other parts can have connections incoming from this one.
 ///
 /// _`build`_ method should convert [`ShapeBase`] instance into `JsonValue`.
+///
+/// _`delay`_ method should return count of ticks the part needs to pass
+/// signal through itself. Logic gates need 1 tick, which is the default.
+///
+/// _`kind`_ method should return short name of the part type (like
+/// `"gate"`, `"timer"` or `"block"`), which is used for introspection.
 /// Examples: [`vanilla::Gate`], [`vanilla:Timer`], [`vanilla::BlockBody`]
+///
+/// _`gate_mode`_ and _`initial_state`_ methods should return mode and
+/// initial state of the part, if it is a logic gate. Other parts return
+/// `None` and `false`.
 pub trait ShapeBase: DynClone + Debug {
 	fn build(&self, data: ShapeBuildData) -> JsonValue;
 
 	fn size(&self) -> Bounds;
 	fn has_input(&self) -> bool;
 	fn has_output(&self) -> bool;
+
+	fn delay(&self) -> u32 {
+		1
+	}
+
+	fn kind(&self) -> &'static str {
+		"custom"
+	}
+
+	fn gate_mode(&self) -> Option<GateMode> {
+		None
+	}
+
+	fn initial_state(&self) -> bool {
+		false
+	}
 }
 dyn_clone::clone_trait_object!(ShapeBase);
 
@@ -134,6 +161,45 @@ impl Shape {
 		self.base.has_output()
 	}
 
+	/// Returns count of ticks the shape needs to pass signal through.
+	pub fn delay(&self) -> u32 {
+		self.base.delay()
+	}
+
+	/// Returns type of the shape (see [`ShapeBase`]).
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::presets::math::adder;
+	/// let scheme = adder(8);
+	/// let gates = scheme.shapes().iter()
+	/// 	.filter(|(_, _, shape)| shape.kind() == "gate")
+	/// 	.count();
+	///
+	/// assert_eq!(gates, scheme.shapes_count());
+	/// ```
+	pub fn kind(&self) -> &'static str {
+		self.base.kind()
+	}
+
+	/// Returns mode of the shape, if it is a logic gate.
+	///
+	/// # Example
+	/// ```
+	/// # use sm_logic::shape::vanilla::{Gate, GateMode, Timer};
+	/// assert_eq!(Gate::new(GateMode::XOR).gate_mode(), Some(GateMode::XOR));
+	/// assert_eq!(Timer::new(3).gate_mode(), None);
+	/// ```
+	pub fn gate_mode(&self) -> Option<GateMode> {
+		self.base.gate_mode()
+	}
+
+	/// Returns whether the shape is active right after the creation is
+	/// placed (see [`vanilla::Gate::with_initial`]).
+	pub fn initial_state(&self) -> bool {
+		self.base.initial_state()
+	}
+
 	/// Compiles shape to JSON
 	pub fn build(&self, pos: Point, rot: Rot, id: usize) -> JsonValue {
 		let data = ShapeBuildData {
@@ -147,6 +213,7 @@ impl Shape {
 		self.base.build(data)
 	}
 
+	/// Forcibly used shapes are not removed by `Scheme::remove_unused`.
 	pub fn is_forcibly_used(&self) -> bool {
 		self.forcibly_used
 	}
@@ -226,6 +226,10 @@ impl ShapeBase for BlockBody {
 	fn has_output(&self) -> bool {
 		false
 	}
+
+	fn kind(&self) -> &'static str {
+		"block"
+	}
 }
 
 impl Into<Shape> for BlockBody {
@@ -0,0 +1,89 @@
+use json::{JsonValue, object};
+use crate::scheme::Scheme;
+use crate::shape::{out_conns_to_controller, Shape, ShapeBase, ShapeBuildData};
+use crate::util::Bounds;
+
+pub const DEFAULT_BUTTON_COLOR: &str = "df7f01";
+pub const BUTTON_UUID: &str = "1e8d93a4-506b-470d-9ada-9c0a321e2db5";
+
+/// Represents "Button" from scrap mechanic. Button can not have
+/// inputs, it is active only while player holds it.
+///
+/// # Example
+/// ```
+/// # use crate::sm_logic::shape::vanilla::Button;
+/// # use crate::sm_logic::shape::Shape;
+/// let button: Shape = Button::new();
+///
+/// assert!(!button.has_input());
+/// assert!(button.has_output());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Button {}
+
+impl Button {
+	pub fn new() -> Shape {
+		Shape::new(
+			Box::new(
+				Button {}
+			)
+		)
+	}
+}
+
+impl ShapeBase for Button {
+	fn build(&self, data: ShapeBuildData) -> JsonValue {
+		let (xaxis, zaxis, offset) = data.rot.to_sm_data();
+		let (x, y, z) = (data.pos + offset).tuple();
+
+		object!{
+			"color": match data.color {
+				None => DEFAULT_BUTTON_COLOR,
+				Some(color) => color,
+			},
+			"shapeId": BUTTON_UUID,
+			"xaxis": xaxis,
+			"zaxis": zaxis,
+			"pos": {
+				"x": x,
+				"y": y,
+				"z": z,
+			},
+			"controller": {
+				"active": false,
+				"id": data.id,
+				"joints": null,
+				"controllers": out_conns_to_controller(data.out_conns),
+			}
+		}
+	}
+
+	fn size(&self) -> Bounds {
+		Bounds::new_ng(1, 1, 1)
+	}
+
+	fn has_input(&self) -> bool {
+		false
+	}
+
+	fn has_output(&self) -> bool {
+		true
+	}
+
+	fn kind(&self) -> &'static str {
+		"button"
+	}
+}
+
+impl Into<Shape> for Button {
+	fn into(self) -> Shape {
+		Shape::new(Box::new(self))
+	}
+}
+
+impl Into<Scheme> for Button {
+	fn into(self) -> Scheme {
+		let shape: Shape = self.into();
+		shape.into()
+	}
+}
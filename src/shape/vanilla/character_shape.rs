@@ -6,6 +6,27 @@ use crate::util::Bounds;
 pub const DEFAULT_CHARACTER_SHAPE_COLOR: &str = "df7f00";
 pub const CHARACTER_SHAPE_UUID: &str = "9f0f56e8-2c31-4d83-996c-d00a9b296c3f";
 
+/// Represents character shape from scrap mechanic. It has no
+/// parameters and can not have inputs or outputs, so it is only
+/// useful as a part of the scheme body.
+///
+/// # Example
+/// ```
+/// # use crate::sm_logic::combiner::Combiner;
+/// # use crate::sm_logic::shape::vanilla::{CharacterShape, CHARACTER_SHAPE_UUID};
+/// # use crate::sm_logic::shape::Shape;
+/// let shape: Shape = CharacterShape::new().into();
+/// assert!(!shape.has_input());
+/// assert!(!shape.has_output());
+///
+/// let mut combiner = Combiner::pos_manual();
+/// combiner.add("character", CharacterShape::new()).unwrap();
+/// combiner.pos().place_last((0, 0, 0));
+///
+/// let (scheme, _) = combiner.compile().unwrap();
+/// let json = scheme.to_json();
+/// assert_eq!(json["bodies"][0]["childs"][0]["shapeId"], CHARACTER_SHAPE_UUID);
+/// ```
 #[derive(Debug, Clone)]
 pub struct CharacterShape {}
 
@@ -53,11 +74,21 @@ impl ShapeBase for CharacterShape {
 	fn has_output(&self) -> bool {
 		false
 	}
+
+	fn kind(&self) -> &'static str {
+		"character"
+	}
+}
+
+impl Into<Shape> for CharacterShape {
+	fn into(self) -> Shape {
+		Shape::new(Box::new(self))
+	}
 }
 
 impl Into<Scheme> for CharacterShape {
 	fn into(self) -> Scheme {
-		let shape: Shape = Shape::new(Box::new(self));
+		let shape: Shape = self.into();
 		shape.into()
 	}
 }
\ No newline at end of file
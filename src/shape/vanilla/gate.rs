@@ -7,7 +7,7 @@ pub const DEFAULT_GATE_COLOR: &str = "df7f00";
 pub const GATE_UUID: &str = "9f0f56e8-2c31-4d83-996c-d00a9b296c3f";
 
 /// Represents all possible states of Logic Gate in Scrap Mechanic
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum GateMode {
 	AND,
 	OR,
@@ -41,6 +41,45 @@ impl GateMode {
 			GateMode::XNOR => 	5,
 		}
 	}
+
+	/// Same as [`GateMode::to_number`], but as `u8`, just like the
+	/// `"mode"` field of the gate controller in blueprint JSON.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::shape::vanilla::GateMode;
+	/// assert_eq!(GateMode::NAND.mode_id(), 3);
+	/// ```
+	pub fn mode_id(&self) -> u8 {
+		self.to_number() as u8
+	}
+
+	/// Inverse of [`GateMode::mode_id`]: returns the mode of Logic Gate
+	/// by the `"mode"` field of its controller in blueprint JSON (only
+	/// gates with [`GATE_UUID`] have it). Unknown numbers give `None`.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::shape::vanilla::GateMode;
+	/// assert_eq!(GateMode::from_mode_id(0), Some(GateMode::AND));
+	/// assert_eq!(GateMode::from_mode_id(5), Some(GateMode::XNOR));
+	/// assert_eq!(GateMode::from_mode_id(6), None);
+	///
+	/// for mode in [GateMode::AND, GateMode::OR, GateMode::XOR, GateMode::NAND, GateMode::NOR, GateMode::XNOR] {
+	/// 	assert_eq!(GateMode::from_mode_id(mode.mode_id()), Some(mode));
+	/// }
+	/// ```
+	pub fn from_mode_id(n: u8) -> Option<GateMode> {
+		match n {
+			0 => Some(GateMode::AND),
+			1 => Some(GateMode::OR),
+			2 => Some(GateMode::XOR),
+			3 => Some(GateMode::NAND),
+			4 => Some(GateMode::NOR),
+			5 => Some(GateMode::XNOR),
+			_ => None,
+		}
+	}
 }
 
 impl Into<Shape> for GateMode {
@@ -72,14 +111,34 @@ impl Into<Scheme> for GateMode {
 #[derive(Debug, Clone)]
 pub struct Gate {
 	mode: GateMode,
+	active: bool,
 }
 
 impl Gate {
 	pub fn new(mode: GateMode) -> Shape {
+		Gate::with_initial(mode, false)
+	}
+
+	/// Creates Logic Gate, which is already active (or not) when the
+	/// creation is placed. Initial state is stored in `"active"` field
+	/// of the gate controller in blueprint JSON. [`Gate::new`] makes
+	/// inactive gates.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::shape::vanilla::{Gate, GateMode};
+	/// # use crate::sm_logic::util::{Point, Rot};
+	/// let gate = Gate::with_initial(GateMode::OR, true);
+	/// let json = gate.build(Point::new_ng(0, 0, 0), Rot::new(0, 0, 0), 0);
+	///
+	/// assert_eq!(json["controller"]["active"], true);
+	/// ```
+	pub fn with_initial(mode: GateMode, active: bool) -> Shape {
 		Shape::new(
 			Box::new(
 				Gate {
-					mode
+					mode,
+					active,
 				}
 			)
 		)
@@ -122,7 +181,7 @@ impl ShapeBase for Gate {
 				"z": z,
 			},
 			"controller": {
-				"active": false,
+				"active": self.active,
 				"id": data.id,
 				"joints": null,
 				"controllers": out_conns_to_controller(data.out_conns),
@@ -142,4 +201,16 @@ impl ShapeBase for Gate {
 	fn has_output(&self) -> bool {
 		true
 	}
+
+	fn kind(&self) -> &'static str {
+		"gate"
+	}
+
+	fn gate_mode(&self) -> Option<GateMode> {
+		Some(self.mode)
+	}
+
+	fn initial_state(&self) -> bool {
+		self.active
+	}
 }
\ No newline at end of file
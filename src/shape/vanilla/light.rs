@@ -0,0 +1,104 @@
+use json::{JsonValue, object};
+use crate::scheme::Scheme;
+use crate::shape::{Shape, ShapeBase, ShapeBuildData};
+use crate::util::Bounds;
+
+pub const DEFAULT_LIGHT_COLOR: &str = "eeeeee";
+pub const LIGHT_UUID: &str = "ed27f5e2-cac5-4a32-a5d9-49f116acc6af";
+
+/// Represents "Light" from scrap mechanic. Light is turned on and off
+/// by logic, but can not have outputs.
+///
+/// `color` is the color of emitted light (hex, like `"eeaf5c"`),
+/// `luminance` is its brightness in percents.
+///
+/// # Example
+/// ```
+/// # use crate::sm_logic::shape::vanilla::Light;
+/// # use crate::sm_logic::shape::Shape;
+/// # use crate::sm_logic::util::{Point, Rot};
+/// let light: Shape = Light::new("ff0000", 50);
+///
+/// assert!(light.has_input());
+/// assert!(!light.has_output());
+///
+/// let json = light.build(Point::new_ng(0, 0, 0), Rot::new(0, 0, 0), 0);
+/// assert_eq!(json["controller"]["color"], "ff0000");
+/// assert_eq!(json["controller"]["luminance"], 50);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Light {
+	color: String,
+	luminance: u32,
+}
+
+impl Light {
+	pub fn new<S: Into<String>>(color: S, luminance: u32) -> Shape {
+		Shape::new(
+			Box::new(
+				Light {
+					color: color.into(),
+					luminance,
+				}
+			)
+		)
+	}
+}
+
+impl ShapeBase for Light {
+	fn build(&self, data: ShapeBuildData) -> JsonValue {
+		let (xaxis, zaxis, offset) = data.rot.to_sm_data();
+		let (x, y, z) = (data.pos + offset).tuple();
+
+		object!{
+			"color": match data.color {
+				None => DEFAULT_LIGHT_COLOR,
+				Some(color) => color,
+			},
+			"shapeId": LIGHT_UUID,
+			"xaxis": xaxis,
+			"zaxis": zaxis,
+			"pos": {
+				"x": x,
+				"y": y,
+				"z": z,
+			},
+			"controller": {
+				"color": self.color.clone(),
+				"coneAngle": 0,
+				"id": data.id,
+				"joints": null,
+				"luminance": self.luminance,
+			}
+		}
+	}
+
+	fn size(&self) -> Bounds {
+		Bounds::new_ng(1, 1, 1)
+	}
+
+	fn has_input(&self) -> bool {
+		true
+	}
+
+	fn has_output(&self) -> bool {
+		false
+	}
+
+	fn kind(&self) -> &'static str {
+		"light"
+	}
+}
+
+impl Into<Shape> for Light {
+	fn into(self) -> Shape {
+		Shape::new(Box::new(self))
+	}
+}
+
+impl Into<Scheme> for Light {
+	fn into(self) -> Scheme {
+		let shape: Shape = self.into();
+		shape.into()
+	}
+}
@@ -5,9 +5,17 @@ mod timer;
 mod block;
 mod character_shape;
 mod totebot_capsule;
+mod switch;
+mod button;
+mod light;
+mod sensor;
 
 pub use gate::*;
 pub use timer::*;
 pub use block::*;
 pub use character_shape::*;
-pub use totebot_capsule::*;
\ No newline at end of file
+pub use totebot_capsule::*;
+pub use switch::*;
+pub use button::*;
+pub use light::*;
+pub use sensor::*;
@@ -0,0 +1,132 @@
+use json::{JsonValue, object};
+use crate::scheme::Scheme;
+use crate::shape::{out_conns_to_controller, Shape, ShapeBase, ShapeBuildData};
+use crate::util::Bounds;
+
+pub const DEFAULT_SENSOR_COLOR: &str = "df7f01";
+pub const DEFAULT_SENSOR_DETECT_COLOR: &str = "eeeeee";
+pub const SENSOR_UUID: &str = "20dcd41c-0a11-4668-9b00-97f278ce21af";
+
+/// Represents "Sensor" from scrap mechanic. Sensor can not have
+/// inputs, it is active while something is in front of it within
+/// `range` blocks.
+///
+/// If `color_filter` is enabled, only objects of `detect_color` color
+/// are detected.
+///
+/// # Example
+/// ```
+/// # use crate::sm_logic::shape::vanilla::Sensor;
+/// # use crate::sm_logic::shape::Shape;
+/// # use crate::sm_logic::util::{Point, Rot};
+/// let sensor: Shape = Sensor::new(10)
+/// 	.detect_color("ff0000")
+/// 	.into();
+///
+/// assert!(!sensor.has_input());
+/// assert!(sensor.has_output());
+///
+/// let json = sensor.build(Point::new_ng(0, 0, 0), Rot::new(0, 0, 0), 0);
+/// assert_eq!(json["controller"]["range"], 10);
+/// assert_eq!(json["controller"]["colorMode"], true);
+/// assert_eq!(json["controller"]["color"], "ff0000");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sensor {
+	range: u32,
+	color_filter: bool,
+	detect_color: String,
+}
+
+impl Sensor {
+	pub fn new(range: u32) -> Sensor {
+		Sensor {
+			range,
+			color_filter: false,
+			detect_color: DEFAULT_SENSOR_DETECT_COLOR.to_string(),
+		}
+	}
+
+	/// Sets detection range in blocks.
+	pub fn range(mut self, range: u32) -> Sensor {
+		self.range = range;
+		self
+	}
+
+	/// Enables or disables color filter. Color, that is detected with
+	/// the filter enabled, is set with [`Sensor::detect_color`].
+	pub fn color_filter(mut self, enabled: bool) -> Sensor {
+		self.color_filter = enabled;
+		self
+	}
+
+	/// Sets color (hex, like `"eeaf5c"`) to detect and enables color
+	/// filter.
+	pub fn detect_color<S: Into<String>>(mut self, color: S) -> Sensor {
+		self.detect_color = color.into();
+		self.color_filter = true;
+		self
+	}
+}
+
+impl ShapeBase for Sensor {
+	fn build(&self, data: ShapeBuildData) -> JsonValue {
+		let (xaxis, zaxis, offset) = data.rot.to_sm_data();
+		let (x, y, z) = (data.pos + offset).tuple();
+
+		object!{
+			"color": match data.color {
+				None => DEFAULT_SENSOR_COLOR,
+				Some(color) => color,
+			},
+			"shapeId": SENSOR_UUID,
+			"xaxis": xaxis,
+			"zaxis": zaxis,
+			"pos": {
+				"x": x,
+				"y": y,
+				"z": z,
+			},
+			"controller": {
+				"active": false,
+				"audioEnabled": false,
+				"buttonMode": true,
+				"color": self.detect_color.clone(),
+				"colorMode": self.color_filter,
+				"id": data.id,
+				"joints": null,
+				"range": self.range,
+				"controllers": out_conns_to_controller(data.out_conns),
+			}
+		}
+	}
+
+	fn size(&self) -> Bounds {
+		Bounds::new_ng(1, 1, 1)
+	}
+
+	fn has_input(&self) -> bool {
+		false
+	}
+
+	fn has_output(&self) -> bool {
+		true
+	}
+
+	fn kind(&self) -> &'static str {
+		"sensor"
+	}
+}
+
+impl Into<Shape> for Sensor {
+	fn into(self) -> Shape {
+		Shape::new(Box::new(self))
+	}
+}
+
+impl Into<Scheme> for Sensor {
+	fn into(self) -> Scheme {
+		let shape: Shape = self.into();
+		shape.into()
+	}
+}
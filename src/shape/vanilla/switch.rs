@@ -0,0 +1,89 @@
+use json::{JsonValue, object};
+use crate::scheme::Scheme;
+use crate::shape::{out_conns_to_controller, Shape, ShapeBase, ShapeBuildData};
+use crate::util::Bounds;
+
+pub const DEFAULT_SWITCH_COLOR: &str = "df7f01";
+pub const SWITCH_UUID: &str = "7cf717d7-d167-4f2d-a6e7-6b2c70aa3986";
+
+/// Represents "Switch" from scrap mechanic. Switch can not have
+/// inputs, its state is toggled by player.
+///
+/// # Example
+/// ```
+/// # use crate::sm_logic::shape::vanilla::Switch;
+/// # use crate::sm_logic::shape::Shape;
+/// let switch: Shape = Switch::new();
+///
+/// assert!(!switch.has_input());
+/// assert!(switch.has_output());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Switch {}
+
+impl Switch {
+	pub fn new() -> Shape {
+		Shape::new(
+			Box::new(
+				Switch {}
+			)
+		)
+	}
+}
+
+impl ShapeBase for Switch {
+	fn build(&self, data: ShapeBuildData) -> JsonValue {
+		let (xaxis, zaxis, offset) = data.rot.to_sm_data();
+		let (x, y, z) = (data.pos + offset).tuple();
+
+		object!{
+			"color": match data.color {
+				None => DEFAULT_SWITCH_COLOR,
+				Some(color) => color,
+			},
+			"shapeId": SWITCH_UUID,
+			"xaxis": xaxis,
+			"zaxis": zaxis,
+			"pos": {
+				"x": x,
+				"y": y,
+				"z": z,
+			},
+			"controller": {
+				"active": false,
+				"id": data.id,
+				"joints": null,
+				"controllers": out_conns_to_controller(data.out_conns),
+			}
+		}
+	}
+
+	fn size(&self) -> Bounds {
+		Bounds::new_ng(1, 1, 1)
+	}
+
+	fn has_input(&self) -> bool {
+		false
+	}
+
+	fn has_output(&self) -> bool {
+		true
+	}
+
+	fn kind(&self) -> &'static str {
+		"switch"
+	}
+}
+
+impl Into<Shape> for Switch {
+	fn into(self) -> Shape {
+		Shape::new(Box::new(self))
+	}
+}
+
+impl Into<Scheme> for Switch {
+	fn into(self) -> Scheme {
+		let shape: Shape = self.into();
+		shape.into()
+	}
+}
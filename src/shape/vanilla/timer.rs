@@ -40,12 +40,37 @@ impl Timer {
 			)
 		)
 	}
+
+	/// Creates timer with delay of given count of seconds.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::shape::vanilla::Timer;
+	/// let timer = Timer::from_seconds(3);
+	/// assert_eq!(timer.delay(), 3 * 40 + 1);
+	/// ```
+	pub fn from_seconds(seconds: u32) -> Shape {
+		Timer::new(seconds * TICKS_PER_SECOND)
+	}
+
+	/// Creates timer with delay of `seconds` seconds + `ticks` ticks.
+	pub fn from_secs_ticks(seconds: u32, ticks: u32) -> Shape {
+		Timer::new(seconds * TICKS_PER_SECOND + ticks)
+	}
+
+	/// Returns the configured delay in ticks (seconds are converted
+	/// to ticks). [`Shape::delay`] of a timer is one tick more.
+	pub fn total_ticks(&self) -> u32 {
+		self.seconds * TICKS_PER_SECOND + self.ticks
+	}
 }
 
 impl ShapeBase for Timer {
 	fn build(&self, data: ShapeBuildData) -> JsonValue {
 		let (xaxis, zaxis, offset) = data.rot.to_sm_data();
 		let (x, y, z) = (data.pos + offset).tuple();
+		// The game expects ticks to be less than a second
+		let total = self.total_ticks();
 
 		object!{
 			"color": match data.color {
@@ -65,8 +90,8 @@ impl ShapeBase for Timer {
 				"id": data.id,
 				"joints": null,
 				"controllers": out_conns_to_controller(data.out_conns),
-				"seconds": self.seconds,
-				"ticks": self.ticks,
+				"seconds": total / TICKS_PER_SECOND,
+				"ticks": total % TICKS_PER_SECOND,
 			}
 		}
 	}
@@ -82,6 +107,16 @@ impl ShapeBase for Timer {
 	fn has_output(&self) -> bool {
 		true
 	}
+
+	fn kind(&self) -> &'static str {
+		"timer"
+	}
+
+	/// Timer holds the signal for configured time, and one more tick
+	/// is needed to pass it, just as with logic gates.
+	fn delay(&self) -> u32 {
+		self.total_ticks() + 1
+	}
 }
 
 impl Into<Shape> for Timer {
@@ -95,4 +130,25 @@ impl Into<Scheme> for Timer {
 		let shape: Shape = self.into();
 		shape.into()
 	}
-}
\ No newline at end of file
+}
+
+#[test]
+fn timer_ticks_test() {
+	use crate::util::{Point, Rot};
+
+	let json = Timer::new(85).build(Point::new_ng(0, 0, 0), Rot::new(0, 0, 0), 0);
+	assert_eq!(json["controller"]["seconds"], 2);
+	assert_eq!(json["controller"]["ticks"], 5);
+
+	// Overflowing ticks are moved into seconds
+	let timer = Timer { seconds: 1, ticks: 45 };
+	assert_eq!(timer.total_ticks(), 85);
+
+	let json = Timer::from_time(1, 45).build(Point::new_ng(0, 0, 0), Rot::new(0, 0, 0), 0);
+	assert_eq!(json["controller"]["seconds"], 2);
+	assert_eq!(json["controller"]["ticks"], 5);
+
+	let json = Timer::from_secs_ticks(1, 45).build(Point::new_ng(0, 0, 0), Rot::new(0, 0, 0), 0);
+	assert_eq!(json["controller"]["seconds"], 2);
+	assert_eq!(json["controller"]["ticks"], 5);
+}
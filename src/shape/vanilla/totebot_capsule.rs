@@ -7,6 +7,26 @@ use crate::util::Bounds;
 pub const DEFAULT_TOTEBOT_CAP_COLOR: &str = "49642d";
 pub const TOTEBOT_CAP_UUID: &str = "34d22fc5-0a45-4d71-9aaf-64df1355c272";
 
+/// Represents "Totebot Capsule" from scrap mechanic - capsule with
+/// a totebot inside. It has no parameters (the game keeps creature
+/// state by itself) and can not have inputs or outputs. Its size is
+/// 7x7x6 blocks.
+///
+/// # Example
+/// ```
+/// # use crate::sm_logic::combiner::Combiner;
+/// # use crate::sm_logic::shape::vanilla::{TotebotCapsule, TOTEBOT_CAP_UUID};
+/// # use crate::sm_logic::util::Bounds;
+/// let mut combiner = Combiner::pos_manual();
+/// combiner.add("capsule", TotebotCapsule::new()).unwrap();
+/// combiner.pos().place_last((0, 0, 0));
+///
+/// let (scheme, _) = combiner.compile().unwrap();
+/// assert_eq!(scheme.bounds(), Bounds::new_ng(7, 7, 6));
+///
+/// let json = scheme.to_json();
+/// assert_eq!(json["bodies"][0]["childs"][0]["shapeId"], TOTEBOT_CAP_UUID);
+/// ```
 #[derive(Debug, Clone)]
 pub struct TotebotCapsule {}
 
@@ -54,6 +74,10 @@ impl ShapeBase for TotebotCapsule {
 	fn has_output(&self) -> bool {
 		false
 	}
+
+	fn kind(&self) -> &'static str {
+		"totebot_capsule"
+	}
 }
 
 impl Into<Shape> for TotebotCapsule {
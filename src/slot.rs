@@ -35,6 +35,27 @@ pub struct SlotSector {
 	pub kind: String,
 }
 
+/// Kinds of slots, which can be connected to each other without any
+/// adaptor: same kinds, single bits (`"logic"` and `"bit"`), and any
+/// kind with `"_"` or empty kind (means "any data").
+///
+/// # Example
+/// ```
+/// # use crate::sm_logic::slot::kinds_compatible;
+/// assert!(kinds_compatible("binary", "binary"));
+/// assert!(kinds_compatible("binary", "_"));
+/// assert!(kinds_compatible("logic", "bit"));
+/// assert!(!kinds_compatible("binary", "bindec"));
+/// ```
+pub fn kinds_compatible(from_kind: &str, to_kind: &str) -> bool {
+	const ANY: [&str; 2] = ["_", ""];
+	const BIT: [&str; 2] = ["logic", "bit"];
+
+	from_kind == to_kind
+		|| ANY.contains(&from_kind) || ANY.contains(&to_kind)
+		|| (BIT.contains(&from_kind) && BIT.contains(&to_kind))
+}
+
 /// # About
 ///
 /// Slot is `Scheme` interface to connect `Scheme` to other schemes.
@@ -139,7 +160,6 @@ pub struct Slot {
 	name: String,
 
 	/// Meaning of the slot and its data
-	#[allow(dead_code)]		// Feature with Slot kinds and adaptors is planned
 	kind: String,
 
 	/// Size of the slot
@@ -0,0 +1,420 @@
+//! Tools for testing schemes without the game: generators of input
+//! vectors and a simple tick-based logic [`Simulator`].
+//!
+//! # Example
+//! ```
+//! # use sm_logic::testing::{exhaustive_inputs, from_bits, Simulator};
+//! # use sm_logic::presets::math::adder;
+//! let mut sim = Simulator::new(&adder(2));
+//!
+//! for input in exhaustive_inputs(4) {
+//! 	let (a, b) = input.split_at(2);
+//! 	sim.set_input("a", a).unwrap();
+//! 	sim.set_input("b", b).unwrap();
+//! 	sim.run(10);
+//!
+//! 	let sum = sim.output_number("_").unwrap() + (sim.output_number("carry").unwrap() << 2);
+//! 	assert_eq!(sum, from_bits(a) + from_bits(b));
+//! }
+//! ```
+
+use std::collections::VecDeque;
+use crate::scheme::{DEFAULT_SLOT, Scheme};
+use crate::shape::Shape;
+use crate::slot::{Slot, SlotSector};
+use crate::util::{Point, split_first_token};
+
+/// Yields all `2^word_size` binary vectors of given size. First element
+/// of each vector is the lowest bit, vectors go in order of the numbers
+/// they represent.
+///
+/// # Example
+/// ```
+/// # use sm_logic::testing::exhaustive_inputs;
+/// let inputs: Vec<Vec<bool>> = exhaustive_inputs(2).collect();
+///
+/// assert_eq!(inputs.len(), 4);
+/// assert_eq!(inputs[0], vec![false, false]);
+/// assert_eq!(inputs[1], vec![true, false]);
+/// assert_eq!(inputs[3], vec![true, true]);
+/// ```
+pub fn exhaustive_inputs(word_size: u32) -> impl Iterator<Item = Vec<bool>> {
+	if word_size >= 64 {
+		panic!("Too big word size for exhaustive inputs ({})", word_size);
+	}
+
+	(0..(1u64 << word_size)).map(move |number| to_bits(number, word_size))
+}
+
+/// Yields `count` pseudo-random binary vectors of given size. Same
+/// `seed` always gives the same vectors.
+///
+/// # Example
+/// ```
+/// # use sm_logic::testing::random_inputs;
+/// let first: Vec<Vec<bool>> = random_inputs(16, 10, 42).collect();
+/// let second: Vec<Vec<bool>> = random_inputs(16, 10, 42).collect();
+///
+/// assert_eq!(first.len(), 10);
+/// assert_eq!(first[0].len(), 16);
+/// assert_eq!(first, second);
+/// ```
+pub fn random_inputs(word_size: u32, count: usize, seed: u64) -> impl Iterator<Item = Vec<bool>> {
+	// Xorshift does not work with zero state
+	let mut state = if seed == 0 { 0x9E3779B97F4A7C15 } else { seed };
+
+	(0..count).map(move |_|
+		(0..word_size)
+			.map(|_| {
+				state ^= state << 13;
+				state ^= state >> 7;
+				state ^= state << 17;
+				state & 1 == 1
+			})
+			.collect()
+	)
+}
+
+/// Converts number into vector of its lowest `word_size` bits. First
+/// element is the lowest bit.
+///
+/// # Example
+/// ```
+/// # use sm_logic::testing::to_bits;
+/// assert_eq!(to_bits(6, 4), vec![false, true, true, false]);
+/// ```
+pub fn to_bits(number: u64, word_size: u32) -> Vec<bool> {
+	(0..word_size)
+		.map(|bit| bit < 64 && (number >> bit) & 1 == 1)
+		.collect()
+}
+
+/// Converts bits (first element is the lowest bit) into number.
+///
+/// # Example
+/// ```
+/// # use sm_logic::testing::from_bits;
+/// assert_eq!(from_bits(&[false, true, true, false]), 6);
+/// ```
+pub fn from_bits(bits: &[bool]) -> u64 {
+	bits.iter()
+		.enumerate()
+		.filter(|(_, bit)| **bit)
+		.map(|(i, _)| 1u64 << i)
+		.sum()
+}
+
+#[derive(Debug, Clone)]
+pub enum SimError {
+	NoSuchSlot {
+		name: String,
+	},
+
+	WrongSize {
+		name: String,
+		expected: usize,
+		got: usize,
+	},
+}
+
+#[derive(Debug, Clone)]
+enum Logic {
+	/// Logic Gate with its mode number
+	Gate(usize),
+	/// Timer with the signals, that are going through it
+	Timer(VecDeque<bool>),
+	/// Anything, that does not process signals
+	Passive,
+}
+
+/// Simple simulator of Scrap Mechanic logic. Each tick every logic gate
+/// calculates its state from the states of shapes connected into it,
+/// timers delay signals for `ticks + 1` ticks. Input slots behave as
+/// if there were switches connected into each of their shapes.
+/// Buttons and switches of the scheme itself are off, unless pressed
+/// with [`Simulator::press`].
+///
+/// Bits of slots are ordered just like raw vector of [`crate::util::Map3D`]:
+/// X changes first, then Y, then Z.
+#[derive(Debug, Clone)]
+pub struct Simulator {
+	logic: Vec<Logic>,
+	sources: Vec<Vec<usize>>,
+	states: Vec<bool>,
+
+	inputs: Vec<Slot>,
+	outputs: Vec<Slot>,
+	input_values: Vec<Vec<bool>>,
+	has_external: Vec<bool>,
+	external: Vec<bool>,
+	pressed: Vec<bool>,
+
+	tick: u64,
+}
+
+impl Simulator {
+	pub fn new(scheme: &Scheme) -> Self {
+		let shapes_count = scheme.shapes_count();
+
+		let logic: Vec<Logic> = scheme.shapes().iter()
+			.map(|(_, _, shape)| shape_logic(shape))
+			.collect();
+
+		let states: Vec<bool> = scheme.shapes().iter()
+			.map(|(_, _, shape)| shape.initial_state())
+			.collect();
+
+		let mut sources: Vec<Vec<usize>> = vec![vec![]; shapes_count];
+		for (source, (_, _, shape)) in scheme.shapes().iter().enumerate() {
+			for target in shape.connections() {
+				// Repeated connection between the same shapes is a single
+				// connection in the game
+				if !sources[*target].contains(&source) {
+					sources[*target].push(source);
+				}
+			}
+		}
+
+		let mut has_external = vec![false; shapes_count];
+		for slot in scheme.inputs() {
+			for shapes in slot.shape_map().as_raw() {
+				for shape in shapes {
+					has_external[*shape] = true;
+				}
+			}
+		}
+
+		Simulator {
+			logic,
+			sources,
+			states,
+			inputs: scheme.inputs().clone(),
+			outputs: scheme.outputs().clone(),
+			input_values: scheme.inputs().iter()
+				.map(|slot| vec![false; slot.shape_map().as_raw().len()])
+				.collect(),
+			has_external,
+			external: vec![false; shapes_count],
+			pressed: vec![false; shapes_count],
+			tick: 0,
+		}
+	}
+
+	/// Count of ticks simulated since creation.
+	pub fn tick(&self) -> u64 {
+		self.tick
+	}
+
+	/// Current state of the shape with given id.
+	pub fn shape_state(&self, id: usize) -> bool {
+		self.states[id]
+	}
+
+	/// Holds (or releases) passive shape with given id, like a button or
+	/// a switch, so it becomes active on the next tick. Logic gates and
+	/// timers are not affected.
+	pub fn press(&mut self, id: usize, active: bool) {
+		self.pressed[id] = active;
+	}
+
+	/// Sets values of input slot or its sector (`"slot/sector"`).
+	pub fn set_input<N: Into<String>>(&mut self, name: N, values: &[bool]) -> Result<(), SimError> {
+		let name = name.into();
+		let (slot_id, sector) = find_slot_sector(&name, &self.inputs)?;
+		let points = sector_points(&self.inputs[slot_id], &sector);
+
+		if points.len() != values.len() {
+			return Err(SimError::WrongSize {
+				name,
+				expected: points.len(),
+				got: values.len(),
+			});
+		}
+
+		for (id, value) in points.into_iter().zip(values) {
+			self.input_values[slot_id][id] = *value;
+		}
+
+		self.update_external();
+		Ok(())
+	}
+
+	/// Sets values of input slot (or its sector) to bits of the number.
+	pub fn set_input_number<N: Into<String>>(&mut self, name: N, number: u64) -> Result<(), SimError> {
+		let name = name.into();
+		let (slot_id, sector) = find_slot_sector(&name, &self.inputs)?;
+		let size = sector_points(&self.inputs[slot_id], &sector).len();
+
+		self.set_input(name, &to_bits(number, size as u32))
+	}
+
+	/// Returns current values of output slot or its sector
+	/// (`"slot/sector"`). Point of the slot is active, if at least
+	/// one of its shapes is active.
+	pub fn output<N: Into<String>>(&self, name: N) -> Result<Vec<bool>, SimError> {
+		let name = name.into();
+		let (slot_id, sector) = find_slot_sector(&name, &self.outputs)?;
+		let raw = self.outputs[slot_id].shape_map().as_raw();
+
+		Ok(
+			sector_points(&self.outputs[slot_id], &sector).into_iter()
+				.map(|id| raw[id].iter().any(|shape| self.states[*shape]))
+				.collect()
+		)
+	}
+
+	/// Returns current values of output slot (or its sector) as number.
+	pub fn output_number<N: Into<String>>(&self, name: N) -> Result<u64, SimError> {
+		self.output(name).map(|bits| from_bits(&bits))
+	}
+
+	/// Simulates one tick.
+	pub fn step(&mut self) {
+		let mut new_states = vec![false; self.states.len()];
+
+		for (id, logic) in self.logic.iter_mut().enumerate() {
+			let mut total = self.sources[id].len();
+			let mut active = self.sources[id].iter()
+				.filter(|source| self.states[**source])
+				.count();
+
+			if self.has_external[id] {
+				total += 1;
+				if self.external[id] {
+					active += 1;
+				}
+			}
+
+			new_states[id] = match logic {
+				Logic::Gate(mode) => total > 0 && match mode {
+					0 => active == total,		// AND
+					1 => active > 0,			// OR
+					2 => active % 2 == 1,		// XOR
+					3 => active < total,		// NAND
+					4 => active == 0,			// NOR
+					_ => active % 2 == 0,		// XNOR
+				},
+
+				Logic::Timer(queue) => {
+					queue.push_back(active > 0);
+					queue.pop_front().unwrap()
+				},
+
+				Logic::Passive => self.pressed[id],
+			};
+		}
+
+		self.states = new_states;
+		self.tick += 1;
+	}
+
+	/// Simulates given count of ticks.
+	pub fn run(&mut self, ticks: u32) {
+		for _ in 0..ticks {
+			self.step();
+		}
+	}
+
+	fn update_external(&mut self) {
+		self.external.iter_mut().for_each(|value| *value = false);
+
+		for (slot, values) in self.inputs.iter().zip(&self.input_values) {
+			for (shapes, value) in slot.shape_map().as_raw().iter().zip(values) {
+				if *value {
+					for shape in shapes {
+						self.external[*shape] = true;
+					}
+				}
+			}
+		}
+	}
+}
+
+fn shape_logic(shape: &Shape) -> Logic {
+	if let Some(mode) = shape.gate_mode() {
+		return Logic::Gate(mode.to_number());
+	}
+
+	if shape.kind() == "timer" {
+		// Timer's delay includes one tick of the update itself
+		let delay = shape.delay().saturating_sub(1) as usize;
+
+		Logic::Timer(VecDeque::from(vec![false; delay]))
+	} else {
+		Logic::Passive
+	}
+}
+
+
+fn find_slot_sector(name: &str, slots: &[Slot]) -> Result<(usize, SlotSector), SimError> {
+	let (slot_name, sector_name) = split_first_token(name.to_string());
+	let slot_name = if slot_name.is_empty() { DEFAULT_SLOT.to_string() } else { slot_name };
+	let sector_name = sector_name.unwrap_or_default();
+
+	let slot_id = slots.iter()
+		.position(|slot| slot.name().eq(&slot_name))
+		.ok_or(SimError::NoSuchSlot { name: name.to_string() })?;
+	let sector = slots[slot_id].get_sector(&sector_name)
+		.ok_or(SimError::NoSuchSlot { name: name.to_string() })?;
+
+	Ok((slot_id, sector.clone()))
+}
+
+/// Ids of the sector points in the raw vector of the slot's shape map.
+fn sector_points(slot: &Slot, sector: &SlotSector) -> Vec<usize> {
+	let (size_x, size_y, size_z) = sector.bounds.cast::<i32>().tuple();
+	let mut points = vec![];
+
+	for z in 0..size_z {
+		for y in 0..size_y {
+			for x in 0..size_x {
+				let point = (sector.pos + Point::new_ng(x, y, z)).cast::<usize>();
+				if let Some(id) = slot.shape_map().to_id(point.tuple()) {
+					points.push(id);
+				}
+			}
+		}
+	}
+
+	points
+}
+
+#[test]
+fn adder_exhaustive_test() {
+	use crate::presets::math::adder;
+
+	let mut sim = Simulator::new(&adder(4));
+
+	for input in exhaustive_inputs(8) {
+		let (a, b) = input.split_at(4);
+		sim.set_input("a", a).unwrap();
+		sim.set_input("b", b).unwrap();
+		sim.run(20);
+
+		let sum = sim.output_number("_").unwrap() + (sim.output_number("carry").unwrap() << 4);
+		assert_eq!(sum, from_bits(a) + from_bits(b), "{} + {}", from_bits(a), from_bits(b));
+	}
+}
+
+#[test]
+fn initial_state_test() {
+	use crate::combiner::Combiner;
+	use crate::shape::vanilla::{Gate, GateMode};
+
+	// OR gate connected to itself keeps its power-on state forever
+	let mut combiner = Combiner::pos_manual();
+	combiner.add("on", Gate::with_initial(GateMode::OR, true)).unwrap();
+	combiner.pos().place_last((0, 0, 0));
+	combiner.add("off", Gate::new(GateMode::OR)).unwrap();
+	combiner.pos().place_last((1, 0, 0));
+	combiner.connect("on", "on");
+	combiner.connect("off", "off");
+	combiner.pass_output("on", "on", None as Option<String>).unwrap();
+	combiner.pass_output("off", "off", None as Option<String>).unwrap();
+	let (scheme, _invalid) = combiner.compile().unwrap();
+
+	let mut sim = Simulator::new(&scheme);
+	sim.run(10);
+	assert_eq!(sim.output("on").unwrap(), vec![true]);
+	assert_eq!(sim.output("off").unwrap(), vec![false]);
+}
@@ -41,6 +41,44 @@ impl<T: Clone> Map3D<T> {
 				.collect()
 		}
 	}
+
+	/// Returns copy of the box with given corner and size, or `None`
+	/// if the box does not fit into the map.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Map3D;
+	///
+	/// let map: Map3D<i32> = Map3D::filled((5, 6, 7), 4);
+	/// let corner = map.slice((4, 5, 6), (1, 1, 1)).unwrap();
+	///
+	/// assert_eq!(corner.size(), (1, 1, 1));
+	/// assert_eq!(corner.get((0, 0, 0)), Some(&4));
+	/// assert!(map.slice((4, 5, 6), (2, 1, 1)).is_none());
+	///
+	/// let map: Map3D<usize> = Map3D::from_raw((3, 2, 1), 0..6);
+	/// let column = map.slice((1, 0, 0), (1, 2, 1)).unwrap();
+	/// assert_eq!(column.to_raw(), vec![1, 4]);
+	/// ```
+	pub fn slice(&self, corner: (usize, usize, usize), size: (usize, usize, usize)) -> Option<Map3D<T>> {
+		if corner.0 + size.0 > self.x_size ||
+			corner.1 + size.1 > self.y_size ||
+			corner.2 + size.2 > self.z_size
+		{
+			return None;
+		}
+
+		let mut data: Vec<T> = Vec::with_capacity(size.0 * size.1 * size.2);
+		for z in 0..size.2 {
+			for y in 0..size.1 {
+				for x in 0..size.0 {
+					data.push(self.get((corner.0 + x, corner.1 + y, corner.2 + z))?.clone());
+				}
+			}
+		}
+
+		Some(Map3D::from_raw(size, data))
+	}
 }
 
 impl<T> Map3D<T> {
@@ -62,6 +100,28 @@ impl<T> Map3D<T> {
 		}
 	}
 
+	/// Creates new Map3D of the same size with every element
+	/// transformed by `f`.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Map3D;
+	///
+	/// let map: Map3D<i32> = Map3D::from_raw((2, 2, 1), [1, 2, 3, 4]);
+	/// let doubled: Map3D<i32> = map.map(|x| x * 2);
+	///
+	/// assert_eq!(doubled.size(), (2, 2, 1));
+	/// assert_eq!(doubled.get((1, 1, 0)), Some(&8));
+	/// ```
+	pub fn map<U, F: Fn(&T) -> U>(&self, f: F) -> Map3D<U> {
+		Map3D {
+			x_size: self.x_size,
+			y_size: self.y_size,
+			z_size: self.z_size,
+			data: self.data.iter().map(f).collect(),
+		}
+	}
+
 	#[allow(dead_code)]
 	pub fn as_raw(&self) -> &Vec<T> {
 		&self.data
@@ -167,6 +227,70 @@ impl<T> Map3D<T> {
 		}
 	}
 
+	/// Returns point, which is stored at given position of raw vector.
+	/// Inverse of [`Map3D::to_id`].
+	pub fn from_id(&self, id: usize) -> Option<(usize, usize, usize)> {
+		if id >= self.data.len() {
+			None
+		} else {
+			Some(id_to_pos(id, self.x_size, self.y_size))
+		}
+	}
+
+	/// Iterates over all the points of the map in the order of raw
+	/// vector: X changes first, then Y, then Z.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Map3D;
+	///
+	/// let map: Map3D<i32> = Map3D::from_raw((2, 2, 2), 0..8);
+	/// let points: Vec<(usize, usize, usize)> = map.iter()
+	/// 	.map(|(pos, _)| pos)
+	/// 	.collect();
+	///
+	/// assert_eq!(points.len(), 8);
+	/// assert_eq!(points[0], (0, 0, 0));
+	/// assert_eq!(points[1], (1, 0, 0));
+	/// assert_eq!(points[2], (0, 1, 0));
+	/// assert_eq!(points[3], (1, 1, 0));
+	/// assert_eq!(points[4], (0, 0, 1));
+	/// assert_eq!(points[7], (1, 1, 1));
+	///
+	/// for (pos, value) in map.iter() {
+	/// 	assert_eq!(map.get(pos), Some(value));
+	/// }
+	/// ```
+	pub fn iter(&self) -> impl Iterator<Item = ((usize, usize, usize), &T)> {
+		let (x_size, y_size) = (self.x_size, self.y_size);
+
+		self.data.iter()
+			.enumerate()
+			.map(move |(id, item)| (id_to_pos(id, x_size, y_size), item))
+	}
+
+	/// Same as [`Map3D::iter`], but gives mutable references.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Map3D;
+	///
+	/// let mut map: Map3D<usize> = Map3D::filled((2, 3, 4), 0);
+	///
+	/// for ((x, y, z), value) in map.iter_mut() {
+	/// 	*value = x + y + z;
+	/// }
+	///
+	/// assert_eq!(map.get((1, 2, 3)), Some(&6));
+	/// ```
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = ((usize, usize, usize), &mut T)> {
+		let (x_size, y_size) = (self.x_size, self.y_size);
+
+		self.data.iter_mut()
+			.enumerate()
+			.map(move |(id, item)| (id_to_pos(id, x_size, y_size), item))
+	}
+
 	/// Converts 3D iterable into Map3D.
 	///
 	/// # Example
@@ -236,4 +360,14 @@ impl<T> Map3D<T> {
 			data,
 		}
 	}
-}
\ No newline at end of file
+}
+
+/// Converts index in the raw vector into position in the map (X
+/// changes first, then Y, then Z).
+fn id_to_pos(id: usize, x_size: usize, y_size: usize) -> (usize, usize, usize) {
+	(
+		id % x_size,
+		(id / x_size) % y_size,
+		id / (x_size * y_size)
+	)
+}
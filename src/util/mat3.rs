@@ -109,6 +109,33 @@ impl Mat3x3 {
 		self[0][0] * self[1][2] * self[2][1] +
 		self[0][1] * self[1][0] * self[2][2]
 	}
+
+	/// Returns transposed matrix (rows become columns).
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Mat3x3;
+	///
+	/// let mat = Mat3x3::from_raw(
+	/// [
+	/// 	[1, 2, 3],
+	/// 	[4, 5, 6],
+	/// 	[7, 8, 9]
+	/// ]);
+	/// assert_eq!(mat.transpose()[0], [1, 4, 7]);
+	/// assert_eq!(mat.transpose().transpose(), mat);
+	/// ```
+	pub fn transpose(&self) -> Mat3x3 {
+		let mut result = Mat3x3::new(0);
+
+		for i in 0..3 {
+			for j in 0..3 {
+				result[i][j] = self[j][i];
+			}
+		}
+
+		result
+	}
 }
 
 impl Mat3x3 {
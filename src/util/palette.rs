@@ -58,4 +58,123 @@ pub fn output_color(output_id: u32, point: Point) -> String {
 	let b = b + (((*point.z() as f32) / 10.0).sin() * (db as f32)).round() as i32;
 
 	color_to_string(r, g, b)
-}
\ No newline at end of file
+}
+
+/// Colors of the in-game paint tool palette. Rows of the palette go
+/// from the lightest to the darkest colors. Can be used everywhere,
+/// where color is accepted as a hex string.
+///
+/// # Example
+/// ```
+/// # use sm_logic::presets::math::adder;
+/// # use sm_logic::util::palette::Color;
+/// let mut scheme = adder(4);
+/// scheme.full_paint(Color::Red);
+///
+/// assert_eq!(Color::Red.hex(), "d02525");
+/// assert_eq!(scheme.shapes()[0].2.get_color(), &Some("d02525".to_string()));
+///
+/// // Raw hex strings are still accepted
+/// scheme.full_paint("eeeeee");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+	// Row 1 of the in-game palette
+	White,
+	LightYellow,
+	LightLime,
+	LightGreen,
+	LightCyan,
+	LightBlue,
+	LightViolet,
+	LightMagenta,
+	LightRed,
+	LightOrange,
+	// Row 2 of the in-game palette
+	Gray,
+	Yellow,
+	Lime,
+	Green,
+	Cyan,
+	Blue,
+	Violet,
+	Magenta,
+	Red,
+	Orange,
+	// Row 3 of the in-game palette
+	DarkGray,
+	DarkYellow,
+	DarkLime,
+	DarkGreen,
+	DarkCyan,
+	DarkBlue,
+	DarkViolet,
+	DarkMagenta,
+	DarkRed,
+	DarkOrange,
+	// Row 4 of the in-game palette
+	Black,
+	DarkestYellow,
+	DarkestLime,
+	DarkestGreen,
+	DarkestCyan,
+	DarkestBlue,
+	DarkestViolet,
+	DarkestMagenta,
+	DarkestRed,
+	DarkestOrange,
+}
+
+impl Color {
+	/// Hex code of the color, as used in blueprint JSON.
+	pub fn hex(&self) -> &'static str {
+		match self {
+			Color::White => "eeeeee",
+			Color::LightYellow => "f5f071",
+			Color::LightLime => "cbf66f",
+			Color::LightGreen => "68ff88",
+			Color::LightCyan => "7eeded",
+			Color::LightBlue => "4c6fe3",
+			Color::LightViolet => "ae79f0",
+			Color::LightMagenta => "ee7bf0",
+			Color::LightRed => "f06767",
+			Color::LightOrange => "eeaf5c",
+			Color::Gray => "7f7f7f",
+			Color::Yellow => "e2db13",
+			Color::Lime => "a0ea00",
+			Color::Green => "19e753",
+			Color::Cyan => "2ce6e6",
+			Color::Blue => "0a3ee2",
+			Color::Violet => "7514ed",
+			Color::Magenta => "cf11d2",
+			Color::Red => "d02525",
+			Color::Orange => "df7f00",
+			Color::DarkGray => "4a4a4a",
+			Color::DarkYellow => "817c00",
+			Color::DarkLime => "577d07",
+			Color::DarkGreen => "0e8031",
+			Color::DarkCyan => "118787",
+			Color::DarkBlue => "0f2e91",
+			Color::DarkViolet => "500aa6",
+			Color::DarkMagenta => "720a74",
+			Color::DarkRed => "7c0000",
+			Color::DarkOrange => "673b00",
+			Color::Black => "222222",
+			Color::DarkestYellow => "323000",
+			Color::DarkestLime => "375000",
+			Color::DarkestGreen => "064023",
+			Color::DarkestCyan => "0a4444",
+			Color::DarkestBlue => "0a1d5a",
+			Color::DarkestViolet => "35086c",
+			Color::DarkestMagenta => "520653",
+			Color::DarkestRed => "560202",
+			Color::DarkestOrange => "472800",
+		}
+	}
+}
+
+impl From<Color> for String {
+	fn from(color: Color) -> Self {
+		color.hex().to_string()
+	}
+}
@@ -54,6 +54,19 @@ impl Rot {
 		}
 	}
 
+	/// Creates [`Rot`] from raw rotation matrix. Matrix is not checked,
+	/// use [`Rot::is_valid`] to check it.
+	pub fn from_matrix(matrix: Mat3x3) -> Self {
+		Rot {
+			matrix
+		}
+	}
+
+	/// Returns raw rotation matrix.
+	pub fn matrix(&self) -> &Mat3x3 {
+		&self.matrix
+	}
+
 	pub fn from_chain<R, I>(rots: I) -> Rot
 		where R: Into<Rot>, I: IntoIterator<Item = R>
 	{
@@ -137,32 +150,137 @@ impl Rot {
 			matrix: self.matrix.clone() * rhs.matrix
 		}
 	}
+
+	/// Returns rotation, which undoes this one.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Rot;
+	/// # use crate::sm_logic::util::Vec3;
+	/// let rot = Rot::new(1, 2, 3);
+	/// let point = Vec3::new_ng(1_i32, 2, 3);
+	///
+	/// assert_eq!(rot.inverse().apply(rot.apply(point)), point);
+	/// ```
+	pub fn inverse(&self) -> Rot {
+		// Rotation matrix is orthonormal, so its inverse is its transpose
+		Rot {
+			matrix: self.matrix.transpose()
+		}
+	}
+
+	/// Returns one of 24 axis-aligned rotations, that transforms `from`
+	/// vector into `to` vector. Both vectors must be unit axis vectors
+	/// (like (0, 0, 1) or (-1, 0, 0)), otherwise `None` is returned.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::{Facing, Rot};
+	/// # use crate::sm_logic::util::Vec3;
+	/// let from = Vec3::new_ng(0_i32, 0, 1);
+	/// let to = Vec3::new_ng(-1_i32, 0, 0);
+	///
+	/// let rot = Rot::from_to(from, to).unwrap();
+	/// assert_eq!(rot.apply(from), to);
+	/// assert_eq!(rot.apply(from), Facing::NegX.to_rot().apply(from));
+	///
+	/// assert_eq!(Rot::from_to(from, Vec3::new_ng(1, 1, 0)), None);
+	/// ```
+	pub fn from_to(from: Vec3<i32>, to: Vec3<i32>) -> Option<Rot> {
+		if !is_unit_axis(from) || !is_unit_axis(to) {
+			return None;
+		}
+
+		for ax in 0..4 {
+			for ay in 0..4 {
+				for az in 0..4 {
+					let rot = Rot::new(ax, ay, az);
+					if rot.apply(from) == to {
+						return Some(rot);
+					}
+				}
+			}
+		}
+
+		None
+	}
+}
+
+fn is_unit_axis(vec: Vec3<i32>) -> bool {
+	let (x, y, z) = vec.tuple();
+	x.abs() + y.abs() + z.abs() == 1
 }
 
 impl Rot {
+	/// Returns true if the rotation is one of 24 rotations, that are
+	/// possible in Scrap Mechanic. Every [`Rot`] created from angles is
+	/// valid, but [`Rot::from_matrix`] allows any matrix.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::{Mat3x3, Rot};
+	/// assert!(Rot::new(1, 2, 3).is_valid());
+	/// assert!(!Rot::from_matrix(Mat3x3::unit(2)).is_valid());
+	/// ```
+	pub fn is_valid(&self) -> bool {
+		self.try_to_facing_orient().is_ok()
+	}
+
 	/// Returns scrap mechanic rotation data:
 	/// "xaxis", "zaxis" and position offset.
+	///
+	/// Panics if the rotation is invalid (see [`Rot::is_valid`]).
 	pub fn to_sm_data(&self) -> (i32, i32, Point) {
-		let (facing, orient) = self.to_facing_orient();
+		match self.try_to_sm_data() {
+			Ok(data) => data,
+			Err(_) => panic!("Rot works incorrectly"),
+		}
+	}
+
+	/// Same as [`Rot::to_sm_data`], but returns error instead of
+	/// panicking on invalid rotation.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::{Mat3x3, Rot, RotError};
+	/// let matrix = Mat3x3::from_raw([[1, 1, 0], [0, 1, 0], [0, 0, 1]]);
+	/// let rot = Rot::from_matrix(matrix.clone());
+	///
+	/// assert_eq!(rot.try_to_sm_data(), Err(RotError::InvalidMatrix(matrix)));
+	/// assert!(Rot::new(0, 1, 0).try_to_sm_data().is_ok());
+	/// ```
+	pub fn try_to_sm_data(&self) -> Result<(i32, i32, Point), RotError> {
+		let (facing, orient) = self.try_to_facing_orient()?;
 		let (xaxis, zaxis, dx, dy, dz) = facing.to_data(orient);
-		(xaxis, zaxis, Point::new(dx, dy, dz))
+		Ok((xaxis, zaxis, Point::new(dx, dy, dz)))
 	}
 
 	/// Converts [`Rot`] to [`Facing`] + [`Orient`] pair.
+	///
+	/// Panics if the rotation is invalid (see [`Rot::is_valid`]).
 	pub fn to_facing_orient(&self) -> (Facing, Orient) {
+		match self.try_to_facing_orient() {
+			Ok(data) => data,
+			Err(_) => panic!("Rot works incorrectly"),
+		}
+	}
+
+	/// Same as [`Rot::to_facing_orient`], but returns error instead of
+	/// panicking on invalid rotation.
+	pub fn try_to_facing_orient(&self) -> Result<(Facing, Orient), RotError> {
 		use Facing::*;
 		use Orient::*;
 
 		let z_axis = self.apply((0, 0, 1).into()).tuple();
 		let x_axis = self.apply((1, 0, 0).into()).tuple();
 
-		return match z_axis {
+		let result = match z_axis {
 			(0, 0, 1) => match x_axis {	// PosZ
 				(0, 1, 0) => (PosZ, Left),
 				(0,-1, 0) => (PosZ, Right),
 				(1, 0, 0) => (PosZ, Up),
 				(-1,0, 0) => (PosZ, Down),
-				_ => panic!("Rot works incorrectly"),
+				_ => return Err(RotError::InvalidMatrix(self.matrix.clone())),
 			},
 
 			(0, 1, 0) => match x_axis {	// PosY
@@ -170,7 +288,7 @@ impl Rot {
 				(0, 0,-1) => (PosY, Down),
 				(1, 0, 0) => (PosY, Left),
 				(-1,0, 0) => (PosY, Right),
-				_ => panic!("Rot works incorrectly"),
+				_ => return Err(RotError::InvalidMatrix(self.matrix.clone())),
 			},
 
 			(1, 0, 0) => match x_axis {	// PosX
@@ -178,7 +296,7 @@ impl Rot {
 				(0, 0,-1) => (PosX, Down),
 				(0, 1, 0) => (PosX, Right),
 				(0,-1, 0) => (PosX, Left),
-				_ => panic!("Rot works incorrectly"),
+				_ => return Err(RotError::InvalidMatrix(self.matrix.clone())),
 			},
 
 			(0, 0,-1) => match x_axis {	// NegZ
@@ -186,7 +304,7 @@ impl Rot {
 				(0,-1, 0) => (NegZ, Left),
 				(1, 0, 0) => (NegZ, Up),
 				(-1,0, 0) => (NegZ, Down),
-				_ => panic!("Rot works incorrectly"),
+				_ => return Err(RotError::InvalidMatrix(self.matrix.clone())),
 			},
 
 			(0,-1, 0) => match x_axis {	// NegY
@@ -194,7 +312,7 @@ impl Rot {
 				(0, 0,-1) => (NegY, Down),
 				(1, 0, 0) => (NegY, Right),
 				(-1,0, 0) => (NegY, Left),
-				_ => panic!("Rot works incorrectly"),
+				_ => return Err(RotError::InvalidMatrix(self.matrix.clone())),
 			},
 
 			(-1,0, 0) => match x_axis {	// NegX
@@ -202,10 +320,58 @@ impl Rot {
 				(0, 0,-1) => (NegX, Down),
 				(0, 1, 0) => (NegX, Left),
 				(0,-1, 0) => (NegX, Right),
-				_ => panic!("Rot works incorrectly"),
+				_ => return Err(RotError::InvalidMatrix(self.matrix.clone())),
 			},
-			_ => panic!("Rot works incorrectly"),
+			_ => return Err(RotError::InvalidMatrix(self.matrix.clone())),
+		};
+
+		// Y axis must be the cross product of Z and X axes, otherwise
+		// the matrix is skewed or mirrored.
+		let (zx, zy, zz) = z_axis;
+		let (xx, xy, xz) = x_axis;
+		let expected_y_axis = (zy * xz - zz * xy, zz * xx - zx * xz, zx * xy - zy * xx);
+
+		if self.apply((0, 1, 0).into()).tuple() != expected_y_axis {
+			return Err(RotError::InvalidMatrix(self.matrix.clone()));
+		}
+
+		Ok(result)
+	}
+
+	/// Inverse of [`Rot::to_sm_data`]: creates rotation from Scrap
+	/// Mechanic "xaxis" and "zaxis" values. Returns `None` if the pair
+	/// is not one of 24 valid rotations.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Rot;
+	/// let rot = Rot::new(0, 1, 2);
+	/// let (xaxis, zaxis, _) = rot.to_sm_data();
+	///
+	/// assert_eq!(Rot::from_sm_axes(xaxis, zaxis), Some(rot));
+	/// assert_eq!(Rot::from_sm_axes(1, 1), None);
+	/// ```
+	pub fn from_sm_axes(xaxis: i32, zaxis: i32) -> Option<Rot> {
+		let id = ROTATIONS_DATA.iter()
+			.position(|(x, z, _, _, _)| *x == xaxis && *z == zaxis)?;
+
+		let facing = match id / 4 {
+			0 => Facing::PosZ,
+			1 => Facing::PosY,
+			2 => Facing::PosX,
+			3 => Facing::NegZ,
+			4 => Facing::NegY,
+			_ => Facing::NegX,
+		};
+
+		let orient = match id % 4 {
+			0 => Orient::Up,
+			1 => Orient::Right,
+			2 => Orient::Down,
+			_ => Orient::Left,
 		};
+
+		Some(Rot::from_facing_orient(facing, orient))
 	}
 
 	pub fn from_facing_orient(facing: Facing, orient: Orient) -> Self {
@@ -227,6 +393,13 @@ impl Rot {
 	}
 }
 
+/// Error of conversion of [`Rot`] into Scrap Mechanic data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RotError {
+	/// Matrix is not one of 24 axis-aligned rotations.
+	InvalidMatrix(Mat3x3),
+}
+
 ///[(xaxis, zaxis, offset_x, offset_y, offset_z)]
 const ROTATIONS_DATA: [(i32, i32, i32, i32, i32); 24] = [
 	( 1, -2, 0,  0, 0),		// PosZ + Up
@@ -328,6 +501,47 @@ impl Facing {
 			Facing::NegZ => Rot::new(2, 0, 0),
 		}
 	}
+
+	/// Returns all six facings.
+	pub fn all() -> [Facing; 6] {
+		[Facing::PosX, Facing::PosY, Facing::PosZ, Facing::NegX, Facing::NegY, Facing::NegZ]
+	}
+
+	/// Returns facing, that is directed the other way.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Facing;
+	/// assert_eq!(Facing::PosX.opposite(), Facing::NegX);
+	/// assert_eq!(Facing::NegZ.opposite(), Facing::PosZ);
+	/// ```
+	pub fn opposite(&self) -> Facing {
+		match self {
+			Facing::PosX => Facing::NegX,
+			Facing::PosY => Facing::NegY,
+			Facing::PosZ => Facing::NegZ,
+			Facing::NegX => Facing::PosX,
+			Facing::NegY => Facing::PosY,
+			Facing::NegZ => Facing::PosZ,
+		}
+	}
+
+	/// Returns axis, along which the facing is directed.
+	pub fn axis(&self) -> Axis {
+		match self {
+			Facing::PosX | Facing::NegX => Axis::X,
+			Facing::PosY | Facing::NegY => Axis::Y,
+			Facing::PosZ | Facing::NegZ => Axis::Z,
+		}
+	}
+}
+
+/// One of three axes of 3D space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+	X,
+	Y,
+	Z,
 }
 
 /// Orientation variants
@@ -387,6 +601,81 @@ fn facing_to_rot_test() {
 	assert_eq!(Vec3::new_ng(0_i32, 0, -1), neg_z);
 }
 
+#[test]
+fn facing_opposite_axis_test() {
+	let vec = Vec3::new_ng(0_i32, 0, 1);
+
+	for facing in Facing::all() {
+		let direction = facing.to_rot().apply(vec);
+		let opposite = facing.opposite().to_rot().apply(vec);
+
+		assert_eq!(opposite, direction * -1);
+		assert_eq!(facing.opposite().opposite(), facing);
+		assert_eq!(facing.axis(), facing.opposite().axis());
+
+		let (x, y, z) = direction.tuple();
+		let axis = match (x != 0, y != 0, z != 0) {
+			(true, false, false) => Axis::X,
+			(false, true, false) => Axis::Y,
+			_ => Axis::Z,
+		};
+		assert_eq!(facing.axis(), axis);
+	}
+}
+
+#[test]
+fn invalid_rot_test() {
+	let matrix = Mat3x3::from_raw([[0, 0, 0], [0, 1, 0], [0, 0, 1]]);
+	let rot = Rot::from_matrix(matrix.clone());
+
+	assert!(!rot.is_valid());
+	assert_eq!(rot.try_to_facing_orient(), Err(RotError::InvalidMatrix(matrix.clone())));
+	assert_eq!(rot.try_to_sm_data(), Err(RotError::InvalidMatrix(matrix)));
+
+	for ax in 0..4 {
+		for ay in 0..4 {
+			for az in 0..4 {
+				assert!(Rot::new(ax, ay, az).is_valid());
+			}
+		}
+	}
+}
+
+#[test]
+fn from_sm_axes_test() {
+	let orients = [Orient::Up, Orient::Down, Orient::Left, Orient::Right];
+
+	for facing in Facing::all() {
+		for orient in orients {
+			let rot = Rot::from_facing_orient(facing, orient);
+			let (xaxis, zaxis, _) = rot.to_sm_data();
+
+			let restored = Rot::from_sm_axes(xaxis, zaxis).unwrap();
+			assert_eq!(restored, rot);
+			assert_eq!(restored.to_facing_orient(), (facing, orient));
+		}
+	}
+
+	assert_eq!(Rot::from_sm_axes(0, 0), None);
+	assert_eq!(Rot::from_sm_axes(1, -1), None);
+	assert_eq!(Rot::from_sm_axes(3, 4), None);
+}
+
+#[test]
+fn inverse_test() {
+	let facings = [Facing::PosX, Facing::PosY, Facing::PosZ, Facing::NegX, Facing::NegY, Facing::NegZ];
+	let orients = [Orient::Up, Orient::Down, Orient::Left, Orient::Right];
+
+	for facing in facings.iter() {
+		for orient in orients.iter() {
+			let rot = Rot::from_facing_orient(*facing, *orient);
+
+			assert_eq!(rot.inverse().apply_to_rot(rot.clone()), Rot::new(0, 0, 0));
+			assert_eq!(rot.apply_to_rot(rot.inverse()), Rot::new(0, 0, 0));
+		}
+	}
+}
+
 impl<N1, N2, N3> Into<Rot> for (N1, N2, N3)
 	where N1: IntoNumber, N2: IntoNumber, N3: IntoNumber
 {
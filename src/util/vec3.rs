@@ -80,6 +80,79 @@ impl<N: Mul<N, Output = N> + Add<N, Output = N>> Vec3<N> {
 	pub fn dot(self, other: Self) -> N {
 		(self.x * other.x) + (self.y * other.y) + (self.z * other.z)
 	}
+
+	/// Returns squared length of the vector (dot product with itself).
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Vec3;
+	/// assert_eq!(Vec3::<i32>::new_ng(1, -2, 3).length_squared(), 14);
+	/// ```
+	pub fn length_squared(self) -> N
+		where N: Copy
+	{
+		self.dot(self)
+	}
+}
+
+impl<N: Copy + Mul<N, Output = N> + Sub<N, Output = N>> Vec3<N> {
+	/// Returns cross product of the vectors. Coordinate system is
+	/// right-handed: X cross Y is Z.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Vec3;
+	/// let x = Vec3::<i32>::new(1, 0, 0);
+	/// let y = Vec3::new(0, 1, 0);
+	///
+	/// assert_eq!(x.cross(y), Vec3::new(0, 0, 1));
+	/// assert_eq!(y.cross(x), Vec3::new(0, 0, -1));
+	/// ```
+	pub fn cross(self, other: Self) -> Vec3<N> {
+		Vec3 {
+			x: self.y * other.z - self.z * other.y,
+			y: self.z * other.x - self.x * other.z,
+			z: self.x * other.y - self.y * other.x,
+		}
+	}
+}
+
+impl<N: Ord> Vec3<N> {
+	/// Returns vector of per-axis minimums of the two vectors.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Point;
+	/// let a = Point::new_ng(1, -5, 3);
+	/// let b = Point::new_ng(2, 0, -3);
+	///
+	/// assert_eq!(a.component_min(b), Point::new_ng(1, -5, -3));
+	/// ```
+	pub fn component_min(self, other: Self) -> Self {
+		Vec3 {
+			x: self.x.min(other.x),
+			y: self.y.min(other.y),
+			z: self.z.min(other.z),
+		}
+	}
+
+	/// Returns vector of per-axis maximums of the two vectors.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Point;
+	/// let a = Point::new_ng(1, -5, 3);
+	/// let b = Point::new_ng(2, 0, -3);
+	///
+	/// assert_eq!(a.component_max(b), Point::new_ng(2, 0, 3));
+	/// ```
+	pub fn component_max(self, other: Self) -> Self {
+		Vec3 {
+			x: self.x.max(other.x),
+			y: self.y.max(other.y),
+			z: self.z.max(other.z),
+		}
+	}
 }
 
 impl<N> Vec3<N> {
@@ -153,6 +226,46 @@ impl<N> Vec3<N> {
 	}
 }
 
+impl Vec3<u32> {
+	/// Treats the vector as bounds and returns amount of points inside
+	/// of them.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::Bounds;
+	/// assert_eq!(Bounds::new_ng(2, 3, 4).volume(), 24);
+	/// assert_eq!(Bounds::new_ng(2, 0, 4).volume(), 0);
+	/// ```
+	pub fn volume(&self) -> u64 {
+		self.x as u64 * self.y as u64 * self.z as u64
+	}
+
+	/// Treats the vector as bounds and iterates over every point inside
+	/// of them. Order is the same as in nested `for x { for y { for z {`
+	/// loops: Z changes first, then Y, then X.
+	///
+	/// # Example
+	/// ```
+	/// # use crate::sm_logic::util::{Bounds, Point};
+	/// let points: Vec<Point> = Bounds::new_ng(2, 2, 1).points().collect();
+	///
+	/// assert_eq!(points.len(), 4);
+	/// assert_eq!(points[0], Point::new_ng(0, 0, 0));
+	/// assert_eq!(points[1], Point::new_ng(0, 1, 0));
+	/// assert_eq!(points[2], Point::new_ng(1, 0, 0));
+	/// assert_eq!(points[3], Point::new_ng(1, 1, 0));
+	/// ```
+	pub fn points(&self) -> impl Iterator<Item = Vec3<i32>> {
+		let (size_x, size_y, size_z) = (self.x as i32, self.y as i32, self.z as i32);
+
+		(0..size_x).flat_map(move |x|
+			(0..size_y).flat_map(move |y|
+				(0..size_z).map(move |z| Vec3::new_ng(x, y, z))
+			)
+		)
+	}
+}
+
 impl<N: Copy> Copy for Vec3<N> {}
 
 impl<N: Clone> Clone for Vec3<N> {